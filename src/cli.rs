@@ -0,0 +1,407 @@
+//! Command-line subcommands. When the first argument is `todo` or a help
+//! flag, Zenith runs the matching command and exits instead of starting the
+//! bar; with anything else the bar starts as usual.
+
+use anyhow::{bail, Context, Result};
+use chrono::Local;
+use glib::variant::{ToVariant, Variant};
+use gtk4::gio;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::PathBuf;
+
+use crate::config::{StorageFormat, ZenithConfig};
+use crate::modules::todo::backend::ListMigration;
+use crate::modules::todo::exchange::{self, Format};
+use crate::modules::todo::{backend, parse, sort, TodoStore, RELOAD_ACTION};
+
+const USAGE: &str = "\
+Usage:
+  zenith                          start the bar
+  zenith todo add <TEXT>          add a task, e.g. \"3:Deploy server tomorrow\"
+  zenith todo list [--json]       print the task list with ids, in the
+                                  configured sort order (* = bar focus)
+  zenith todo done <ID>           check a task off
+  zenith todo rm <ID>             delete a task
+  zenith todo mv <ID> <LIST>      move a task to another list
+  zenith todo lists               print the configured lists (* = default)
+  zenith todo export [--format md|json] [FILE]
+                                  write the list as a markdown checklist
+                                  or JSON (to stdout without FILE)
+  zenith todo import [--format md|json] [FILE]
+                                  add the tasks from a markdown checklist
+                                  or JSON (from stdin without FILE)
+  zenith todo migrate --to <FORMAT> [--to-path <PATH>]
+                      [--from <FORMAT>] [--from-path <PATH>]
+                                  copy the task lists into another format
+                                  (json, todo.txt, sqlite or taskwarrior);
+                                  the source defaults to the configured one
+
+Task commands work on the default list; add `--list <NAME>` to pick another.
+Everything after `add` is the task's text, so there it goes before the
+command: zenith todo --list work add call Bob";
+
+/// Whether `args` (program name excluded) name one of our commands.
+pub fn handles(args: &[String]) -> bool {
+    matches!(
+        args.first().map(String::as_str),
+        Some("todo" | "help" | "--help" | "-h")
+    )
+}
+
+/// Run the command in `args` (program name excluded).
+pub fn run(args: &[String], cfg: &ZenithConfig) -> Result<()> {
+    let mut words: Vec<&str> = args.iter().map(String::as_str).collect();
+    // `--list <NAME>` stands in for `default_list` in the config.
+    let mut cfg = cfg.clone();
+    if let Some(name) = take_list_option(&mut words)? {
+        cfg.todo.default_list = Some(name.to_string());
+    }
+    let cfg = &cfg;
+    match words.as_slice() {
+        ["todo", "add", text @ ..] if !text.is_empty() => todo_add(&text.join(" "), cfg),
+        ["todo", "list"] => todo_list(false, cfg),
+        ["todo", "list", "--json"] => todo_list(true, cfg),
+        ["todo", "done", id] => todo_done(parse_id(id)?, cfg),
+        ["todo", "rm", id] => todo_rm(parse_id(id)?, cfg),
+        ["todo", "mv", id, list] => todo_mv(parse_id(id)?, list, cfg),
+        ["todo", "lists"] => todo_lists(cfg),
+        ["todo", "export", rest @ ..] => todo_export(rest, cfg),
+        ["todo", "import", rest @ ..] => todo_import(rest, cfg),
+        ["todo", "migrate", rest @ ..] => todo_migrate(rest, cfg),
+        ["help" | "--help" | "-h"] => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => bail!("Unknown command: {}\n\n{USAGE}", args.join(" ")),
+    }
+}
+
+/// Remove `--list <NAME>` from `words` and return the name. Option parsing
+/// stops at `--` (which is dropped) and at the text of `todo add`, so a task
+/// may mention `--list` itself.
+fn take_list_option<'a>(words: &mut Vec<&'a str>) -> Result<Option<&'a str>> {
+    let mut name = None;
+    let mut i = 0;
+    while i < words.len() {
+        match words[i] {
+            "--" => {
+                words.remove(i);
+                break;
+            }
+            "add" if i == 1 => break,
+            "--list" => {
+                let Some(&value) = words.get(i + 1) else {
+                    bail!("Missing value for --list");
+                };
+                name = Some(value);
+                words.drain(i..i + 2);
+            }
+            _ => i += 1,
+        }
+    }
+    Ok(name)
+}
+
+fn parse_id(arg: &str) -> Result<u64> {
+    arg.parse()
+        .with_context(|| format!("Not a task id: {arg} (see `zenith todo list`)"))
+}
+
+fn open_store(cfg: &ZenithConfig) -> Result<TodoStore> {
+    let store = TodoStore::open(&cfg.todo);
+    if let Some(ref name) = cfg.todo.default_list {
+        list_index(&store, name)?;
+    }
+    Ok(store)
+}
+
+fn list_index(store: &TodoStore, name: &str) -> Result<usize> {
+    store.list_index(name).with_context(|| {
+        let names: Vec<&str> = store.list_names().collect();
+        format!("No list named {name:?} (lists: {})", names.join(", "))
+    })
+}
+
+/// Save `store` and tell a running bar to re-read it.
+fn commit(store: &mut TodoStore) {
+    store.save();
+    notify_bar();
+}
+
+fn todo_add(text: &str, cfg: &ZenithConfig) -> Result<()> {
+    let mut store = open_store(cfg)?;
+    let id = store.add(text, Local::now().naive_local());
+    commit(&mut store);
+    println!("Added task {id}");
+    Ok(())
+}
+
+fn todo_list(json: bool, cfg: &ZenithConfig) -> Result<()> {
+    let store = open_store(cfg)?;
+    if json {
+        let items: Vec<_> = store.active_items().collect();
+        println!("{}", serde_json::to_string_pretty(&items)?);
+        return Ok(());
+    }
+    let order = sort::order(&store.items, cfg.todo.sort, Local::now().naive_local());
+    for item in order
+        .into_iter()
+        .map(|i| &store.items[i])
+        .filter(|t| t.list == store.active)
+    {
+        let check = if item.done { "x" } else { " " };
+        let pin = if item.focus { " *" } else { "" };
+        println!(
+            "{:>4}  [{check}] {}{pin}",
+            item.id,
+            parse::format_task(item)
+        );
+    }
+    Ok(())
+}
+
+fn todo_done(id: u64, cfg: &ZenithConfig) -> Result<()> {
+    let mut store = open_store(cfg)?;
+    let Some(item) = store.find_mut(id) else {
+        bail!("No task with id {id}");
+    };
+    if item.done {
+        bail!("Task {id} is already done");
+    }
+    item.complete(Local::now().naive_local());
+    // Recurring tasks stay open with their next due date.
+    let next_due = item.due.filter(|_| !item.done).map(|d| d.date);
+    commit(&mut store);
+    match next_due {
+        Some(date) => println!("Completed task {id}; next due {date}"),
+        None => println!("Completed task {id}"),
+    }
+    Ok(())
+}
+
+fn todo_mv(id: u64, list: &str, cfg: &ZenithConfig) -> Result<()> {
+    let mut store = open_store(cfg)?;
+    let list = list_index(&store, list)?;
+    if !store.move_to_list(id, list) {
+        bail!("No task with id {id}");
+    }
+    commit(&mut store);
+    let name = store.list_names().nth(list).unwrap_or_default();
+    println!("Moved task {id} to {name}");
+    Ok(())
+}
+
+fn todo_lists(cfg: &ZenithConfig) -> Result<()> {
+    let store = open_store(cfg)?;
+    for (list, name) in store.list_names().enumerate() {
+        let mark = if list == store.active { "*" } else { " " };
+        let pending = store
+            .items
+            .iter()
+            .filter(|t| t.list == list && !t.done)
+            .count();
+        println!("{mark} {name} ({pending} pending)");
+    }
+    Ok(())
+}
+
+fn todo_rm(id: u64, cfg: &ZenithConfig) -> Result<()> {
+    let mut store = open_store(cfg)?;
+    let Some(item) = store.remove(id) else {
+        bail!("No task with id {id}");
+    };
+    commit(&mut store);
+    println!("Removed task {id}: {}", item.text);
+    Ok(())
+}
+
+/// Split `[--format md|json] [FILE]`; a FILE of "-" means stdin/stdout.
+fn exchange_args<'a>(args: &[&'a str]) -> Result<(Option<Format>, Option<&'a str>)> {
+    let mut format = None;
+    let mut file = None;
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        match *arg {
+            "--format" => {
+                let value = it.next().context("Missing value for --format")?;
+                format = Some(value.parse()?);
+            }
+            _ if file.is_none() => file = Some(*arg),
+            _ => bail!("Unexpected argument {arg}\n\n{USAGE}"),
+        }
+    }
+    Ok((format, file.filter(|f| *f != "-")))
+}
+
+fn todo_export(args: &[&str], cfg: &ZenithConfig) -> Result<()> {
+    let (format, file) = exchange_args(args)?;
+    let format = format
+        .or_else(|| file.and_then(|f| Format::from_path(f.as_ref())))
+        .unwrap_or(Format::Markdown);
+    let store = open_store(cfg)?;
+    let items: Vec<_> = store.active_items().cloned().collect();
+    let out = exchange::export(&items, format)?;
+    match file {
+        Some(path) => {
+            std::fs::write(path, out).with_context(|| format!("Failed to write {path}"))?;
+            println!("Exported {} task(s) to {path}", items.len());
+        }
+        None => print!("{out}"),
+    }
+    Ok(())
+}
+
+fn todo_import(args: &[&str], cfg: &ZenithConfig) -> Result<()> {
+    let (format, file) = exchange_args(args)?;
+    let contents = match file {
+        Some(path) => {
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read {path}"))?
+        }
+        None => {
+            let mut buf = String::new();
+            std::io::stdin().read_to_string(&mut buf)?;
+            buf
+        }
+    };
+    let format = format
+        .or_else(|| file.and_then(|f| Format::from_path(f.as_ref())))
+        .unwrap_or_else(|| Format::sniff(&contents));
+    let items = exchange::import(&contents, format, Local::now().naive_local())?;
+
+    let mut store = open_store(cfg)?;
+    let count = store.append(items);
+    commit(&mut store);
+    println!("Imported {count} task(s)");
+    Ok(())
+}
+
+/// Activate the reload action of a running bar over D-Bus. Nothing to do
+/// when the bar is not running.
+fn notify_bar() {
+    let bus = match gio::bus_get_sync(gio::BusType::Session, gio::Cancellable::NONE) {
+        Ok(bus) => bus,
+        Err(e) => {
+            log::debug!("No session bus, not notifying the bar: {e}");
+            return;
+        }
+    };
+    let params = (
+        RELOAD_ACTION,
+        Vec::<Variant>::new(),
+        HashMap::<String, Variant>::new(),
+    );
+    let object_path = format!("/{}", crate::APP_ID.replace('.', "/"));
+    if let Err(e) = bus.call_sync(
+        Some(crate::APP_ID),
+        &object_path,
+        "org.freedesktop.Application",
+        "ActivateAction",
+        Some(&params.to_variant()),
+        None,
+        gio::DBusCallFlags::NO_AUTO_START,
+        1000,
+        gio::Cancellable::NONE,
+    ) {
+        log::debug!("Bar not notified (probably not running): {e}");
+    }
+}
+
+fn todo_migrate(args: &[&str], cfg: &ZenithConfig) -> Result<()> {
+    let mut from = None;
+    let mut from_path = None;
+    let mut to = None;
+    let mut to_path = None;
+
+    let mut it = args.iter();
+    while let Some(flag) = it.next() {
+        let value = it
+            .next()
+            .with_context(|| format!("Missing value for {flag}"))?;
+        match *flag {
+            "--from" => from = Some(value.parse::<StorageFormat>()?),
+            "--from-path" => from_path = Some(PathBuf::from(value)),
+            "--to" => to = Some(value.parse::<StorageFormat>()?),
+            "--to-path" => to_path = Some(PathBuf::from(value)),
+            _ => bail!("Unknown option {flag}\n\n{USAGE}"),
+        }
+    }
+    let Some(to) = to else {
+        bail!("--to is required\n\n{USAGE}");
+    };
+
+    // Without `--from` the source is the configured list.
+    let (from, from_path) = match from {
+        Some(format) => (format, from_path),
+        None => (cfg.todo.format, from_path.or_else(|| cfg.todo.path.clone())),
+    };
+
+    // Every configured list moves over, each to its own file.
+    let lists = backend::migration(&cfg.todo, (from, from_path), (to, to_path.clone()));
+    for list in &lists {
+        if list.source.path() == list.target.path() {
+            bail!(
+                "Source and target are both {}",
+                list.source.path().display()
+            );
+        }
+    }
+    for ListMigration {
+        name,
+        source,
+        target,
+    } in &lists
+    {
+        let count = backend::migrate(source.as_ref(), target.as_ref())?;
+        let list = match lists.len() {
+            1 => String::new(),
+            _ => format!(" ({name})"),
+        };
+        println!(
+            "Copied {count} task(s){list} from {} to {}",
+            source.path().display(),
+            target.path().display()
+        );
+    }
+    if matches!(to, StorageFormat::TodoTxt | StorageFormat::Taskwarrior) {
+        println!("Note: {to} does not keep subtasks, recurring-task history or tracked time.");
+    }
+    println!("Set `format = \"{to}\"` under [todo] in config.toml to use it.");
+    if let Some(path) = to_path {
+        println!("Also set `path = \"{}\"` there.", path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(line: &str) -> (Option<String>, String) {
+        let mut words: Vec<&str> = line.split(' ').collect();
+        let name = take_list_option(&mut words).unwrap().map(String::from);
+        (name, words.join(" "))
+    }
+
+    #[test]
+    fn list_option_stops_at_the_task_text() {
+        assert_eq!(
+            split("todo --list work add call Bob"),
+            (Some("work".into()), "todo add call Bob".into())
+        );
+        assert_eq!(
+            split("todo add call Bob --list work"),
+            (None, "todo add call Bob --list work".into())
+        );
+        assert_eq!(
+            split("todo list --json --list home"),
+            (Some("home".into()), "todo list --json".into())
+        );
+        assert_eq!(
+            split("todo mv 3 -- --list"),
+            (None, "todo mv 3 --list".into())
+        );
+        let mut words = vec!["todo", "list", "--list"];
+        assert!(take_list_option(&mut words).is_err());
+    }
+}
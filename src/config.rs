@@ -1,5 +1,7 @@
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use chrono::{Locale, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Deserializer};
 use std::fs;
 use std::path::PathBuf;
 
@@ -10,6 +12,8 @@ use std::path::PathBuf;
 pub struct ZenithConfig {
     pub bar: BarConfig,
     pub modules: ModulesConfig,
+    pub todo: TodoConfig,
+    pub pomodoro: PomodoroConfig,
 }
 
 /// Configuration for bar geometry, positioning, and appearance.
@@ -41,8 +45,228 @@ pub struct BarConfig {
 pub struct ModulesConfig {
     pub clock: bool,
     pub clock_format: String,
+    /// Shown instead of `clock_format` while toggled by a click.
+    pub clock_format_alt: Option<String>,
+    /// More formats to scroll through after `clock_format`.
+    pub clock_formats: Vec<String>,
+    /// The date part of the clock's tooltip; empty to leave it out.
+    pub clock_tooltip_format: String,
+    /// Other time zones the clock can show besides local time.
+    pub clock_zones: Vec<ClockZone>,
+    /// strftime format of the date on the calendar button.
+    pub calendar_format: String,
+    /// Language for month and day names, such as "de_DE". Defaults to
+    /// `LC_ALL`, `LC_TIME` or `LANG`.
+    #[serde(deserialize_with = "deserialize_locale")]
+    pub calendar_locale: Option<Locale>,
+    /// First column of the popover calendar. Defaults to the locale's.
+    pub first_weekday: Option<Weekday>,
     pub system_stats: bool,
     pub todo: bool,
+    pub pomodoro: bool,
+}
+
+/// A time zone for the clock, by IANA name.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClockZone {
+    #[serde(deserialize_with = "deserialize_tz")]
+    pub zone: Tz,
+    /// Shown before the time; defaults to the last part of the zone name.
+    pub label: Option<String>,
+}
+
+impl ClockZone {
+    pub fn label(&self) -> String {
+        match self.label {
+            Some(ref label) => label.clone(),
+            None => {
+                let name = self.zone.name();
+                name.rsplit('/').next().unwrap_or(name).replace('_', " ")
+            }
+        }
+    }
+}
+
+/// Parse an IANA zone name, so a typo fails at config load.
+fn deserialize_tz<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Tz, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    name.parse().map_err(|_| {
+        serde::de::Error::custom(format!(
+            "unknown time zone {name:?} (expected an IANA name such as \"Europe/Berlin\")"
+        ))
+    })
+}
+
+/// Parse a locale name, so a typo fails at config load.
+fn deserialize_locale<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<Locale>, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    parse_locale(&name).map(Some).ok_or_else(|| {
+        serde::de::Error::custom(format!(
+            "unknown locale {name:?} (expected a name such as \"de_DE\")"
+        ))
+    })
+}
+
+/// The most weeks of history the stats heatmap shows; more would not fit
+/// the popover.
+pub const MAX_STATS_WEEKS: u32 = 20;
+
+/// Clamp `stats_weeks` into range, saying so when that changes the value.
+fn deserialize_stats_weeks<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<u32, D::Error> {
+    let weeks = u32::deserialize(deserializer)?;
+    let clamped = weeks.clamp(1, MAX_STATS_WEEKS);
+    if clamped != weeks {
+        log::warn!("todo.stats_weeks = {weeks} is out of range, using {clamped}");
+    }
+    Ok(clamped)
+}
+
+/// A locale from a POSIX name such as "de_DE.UTF-8@euro". The codeset is
+/// ignored; "C" means POSIX.
+pub fn parse_locale(name: &str) -> Option<Locale> {
+    let (base, modifier) = match name.split_once('@') {
+        Some((base, modifier)) => (base, Some(modifier)),
+        None => (name, None),
+    };
+    let base = base.split('.').next().unwrap_or(base);
+    if base == "C" {
+        return Some(Locale::POSIX);
+    }
+    modifier
+        .and_then(|m| Locale::try_from(format!("{base}@{m}").as_str()).ok())
+        .or_else(|| Locale::try_from(base).ok())
+}
+
+/// Behaviour of the todo module.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct TodoConfig {
+    /// On-disk format of the task list.
+    pub format: StorageFormat,
+    /// Where the task list lives. Defaults to a file named after the format
+    /// in `$XDG_DATA_HOME/zenith`; for Taskwarrior, the data directory
+    /// (`$TASKDATA` or `~/.task`).
+    pub path: Option<PathBuf>,
+    /// Check a task off automatically once all of its subtasks are done.
+    pub auto_complete_parent: bool,
+    /// Move completed tasks to the archive this many hours after they were
+    /// checked off. `None` disables age-based archiving.
+    pub archive_after_hours: Option<u32>,
+    /// Move tasks completed on a previous day to the archive at midnight.
+    pub archive_at_midnight: bool,
+    /// Two-way sync with a CalDAV task list. `None` keeps the list local.
+    pub caldav: Option<CaldavConfig>,
+    /// Initial order of the popover list, also used to pick the task shown
+    /// on the bar when none is pinned as the focus.
+    pub sort: SortMode,
+    /// Names of separate task lists. The first one lives at `path`, the
+    /// others in files next to it. Empty means a single unnamed list.
+    pub lists: Vec<String>,
+    /// List the bar shows on start. Defaults to the first one.
+    pub default_list: Option<String>,
+    /// Weeks of history in the stats page heatmap, 1 to
+    /// [`MAX_STATS_WEEKS`]; values outside are clamped with a warning.
+    #[serde(deserialize_with = "deserialize_stats_weeks")]
+    pub stats_weeks: u32,
+}
+
+/// How tasks are ordered.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortMode {
+    /// The order tasks were added or dragged into.
+    #[default]
+    Manual,
+    /// Priority 1 first, tasks without one last.
+    Priority,
+    /// Earliest due date first, undated tasks last.
+    Due,
+    /// Most recently added first.
+    Created,
+    /// A score combining priority, due date and age.
+    Smart,
+}
+
+/// Phase lengths of the pomodoro timer.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct PomodoroConfig {
+    /// Length of a work session in minutes.
+    pub work_minutes: u32,
+    /// Length of the break after most work sessions.
+    pub short_break_minutes: u32,
+    /// Length of the break after every `long_break_every`-th session.
+    pub long_break_minutes: u32,
+    pub long_break_every: u32,
+    /// Start the next phase right away instead of waiting for a click.
+    pub auto_start: bool,
+}
+
+/// A CalDAV task collection to keep the list in sync with.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct CaldavConfig {
+    /// URL of the task collection (e.g.
+    /// "https://dav.example.com/user/tasks/").
+    pub url: String,
+    /// Username for HTTP Basic auth. Empty sends no credentials.
+    pub username: String,
+    /// Password in plain text. Prefer `password_command`.
+    pub password: Option<String>,
+    /// Shell command whose first output line is the password
+    /// (e.g. "pass show dav").
+    pub password_command: Option<String>,
+    /// Seconds between background syncs.
+    pub interval_secs: u32,
+}
+
+/// How the task list is stored.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StorageFormat {
+    /// `todos.json`, Zenith's native format.
+    #[default]
+    #[serde(rename = "json")]
+    Json,
+    /// `todo.txt`, one task per line following the todo.txt spec.
+    #[serde(rename = "todo.txt")]
+    TodoTxt,
+    /// `todos.db`, an SQLite database.
+    #[serde(rename = "sqlite")]
+    Sqlite,
+    /// An existing Taskwarrior database, via the `task` command.
+    #[serde(rename = "taskwarrior")]
+    Taskwarrior,
+}
+
+impl std::fmt::Display for StorageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            StorageFormat::Json => "json",
+            StorageFormat::TodoTxt => "todo.txt",
+            StorageFormat::Sqlite => "sqlite",
+            StorageFormat::Taskwarrior => "taskwarrior",
+        })
+    }
+}
+
+impl std::str::FromStr for StorageFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "json" => Ok(StorageFormat::Json),
+            "todo.txt" => Ok(StorageFormat::TodoTxt),
+            "sqlite" => Ok(StorageFormat::Sqlite),
+            "taskwarrior" => Ok(StorageFormat::Taskwarrior),
+            _ => anyhow::bail!(
+                "Unknown todo format {s:?} (expected json, todo.txt, sqlite or taskwarrior)"
+            ),
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -69,8 +293,57 @@ impl Default for ModulesConfig {
         Self {
             clock: true,
             clock_format: "%H:%M:%S".into(),
+            clock_format_alt: None,
+            clock_formats: Vec::new(),
+            clock_tooltip_format: "%A %d %B %Y\nWeek %V, day %j".into(),
+            clock_zones: Vec::new(),
+            calendar_format: "%d %b".into(),
+            calendar_locale: None,
+            first_weekday: None,
             system_stats: true,
             todo: true,
+            pomodoro: false,
+        }
+    }
+}
+
+impl Default for TodoConfig {
+    fn default() -> Self {
+        Self {
+            format: StorageFormat::Json,
+            path: None,
+            auto_complete_parent: true,
+            archive_after_hours: None,
+            archive_at_midnight: false,
+            caldav: None,
+            sort: SortMode::Manual,
+            lists: Vec::new(),
+            default_list: None,
+            stats_weeks: 12,
+        }
+    }
+}
+
+impl Default for PomodoroConfig {
+    fn default() -> Self {
+        Self {
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            long_break_every: 4,
+            auto_start: true,
+        }
+    }
+}
+
+impl Default for CaldavConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            username: String::new(),
+            password: None,
+            password_command: None,
+            interval_secs: 300,
         }
     }
 }
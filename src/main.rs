@@ -1,6 +1,9 @@
+mod cli;
 mod config;
 mod modules;
+mod notify;
 mod style;
+mod timesync;
 mod ui;
 
 use anyhow::Result;
@@ -17,6 +20,14 @@ fn main() -> Result<()> {
     let cfg = config::load()?;
     log::debug!("Config: {:#?}", cfg);
 
+    modules::todo::backend::move_legacy_data(&cfg.todo)?;
+
+    // Subcommands (e.g. `zenith todo migrate`) run without starting GTK.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if cli::handles(&args) {
+        return cli::run(&args, &cfg);
+    }
+
     let app = Application::builder().application_id(APP_ID).build();
 
     // Move the config into the activation closure.
@@ -1,27 +1,42 @@
-use chrono::Local;
+use chrono::{Datelike, Days, Local, Locale, Months, NaiveDate, Weekday};
 use glib;
 use gtk4::prelude::*;
-use gtk4::{Button, Calendar, Popover};
+use gtk4::{Button, Grid, Label, Orientation, Popover};
+use std::cell::Cell;
+use std::fmt::Write;
+use std::rc::Rc;
 use std::time::Duration;
 
+use crate::config::{parse_locale, ModulesConfig};
+
+/// Rows of days in the popover, enough for any month.
+const WEEKS: u64 = 6;
+
 /// Create a clickable date button that opens a slide-down calendar popover.
 ///
-/// Displays the current date as "21 Feb". Clicking it toggles a popover
-/// containing a full GTK4 Calendar widget.
-pub fn create() -> Button {
+/// Displays the current date in `calendar_format` ("21 Feb" by default),
+/// with month and day names in `calendar_locale`. Clicking it toggles a
+/// popover with a month grid whose weeks start on `first_weekday`, which
+/// the arrow keys move through.
+pub fn create(cfg: &ModulesConfig) -> Button {
+    let locale = cfg.calendar_locale.unwrap_or_else(env_locale);
+    let first_weekday = cfg
+        .first_weekday
+        .unwrap_or_else(|| locale_first_weekday(locale));
+    let format = cfg.calendar_format.clone();
+
     // The button label *is* the date text – no separate icon.
     let btn = Button::new();
     btn.add_css_class("zenith-calendar-btn");
     btn.add_css_class("zenith-module");
     btn.add_css_class("zenith-module-center");
-    update_button_label(&btn);
+    update_button_label(&btn, &format, locale);
 
     // ── Calendar popover ────────────────────────────────────────
-    let calendar = Calendar::new();
-    calendar.add_css_class("zenith-calendar");
+    let month = MonthView::new(locale, first_weekday);
 
     let popover = Popover::new();
-    popover.set_child(Some(&calendar));
+    popover.set_child(Some(&month.root));
     popover.set_autohide(true);
     popover.set_cascade_popdown(true);
     popover.set_has_arrow(false);
@@ -29,14 +44,16 @@ pub fn create() -> Button {
     popover.add_css_class("zenith-calendar-popup");
     popover.set_parent(&btn);
 
-    // Toggle on click
+    // Toggle on click, opening on the current month
     btn.connect_clicked({
         let popover = popover.clone();
         move |_| {
             if popover.is_visible() {
                 popover.popdown();
             } else {
+                month.show_today();
                 popover.popup();
+                month.focus_selected();
             }
         }
     });
@@ -45,7 +62,7 @@ pub fn create() -> Button {
     let weak_btn = btn.downgrade();
     glib::timeout_add_local(Duration::from_secs(60), move || {
         if let Some(b) = weak_btn.upgrade() {
-            update_button_label(&b);
+            update_button_label(&b, &format, locale);
             glib::ControlFlow::Continue
         } else {
             glib::ControlFlow::Break
@@ -55,8 +72,320 @@ pub fn create() -> Button {
     btn
 }
 
-/// Set the button label to the current date in "DD Mon" format.
-fn update_button_label(btn: &Button) {
-    let now = Local::now();
-    btn.set_label(&now.format("%d %b").to_string());
+/// Set the button label to the current date in `format`.
+fn update_button_label(btn: &Button, format: &str, locale: Locale) {
+    btn.set_label(&localized(Local::now().date_naive(), format, locale));
+}
+
+/// `date` in `format` with `locale`'s names, or the format itself when it
+/// has a bad specifier (chrono's `to_string` would panic).
+fn localized(date: NaiveDate, format: &str, locale: Locale) -> String {
+    let mut out = String::new();
+    match write!(out, "{}", date.format_localized(format, locale)) {
+        Ok(()) => out,
+        Err(_) => format.to_string(),
+    }
+}
+
+/// The locale for dates from `LC_ALL`, `LC_TIME` or `LANG`, like libc.
+fn env_locale() -> Locale {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| parse_locale(&value))
+        .unwrap_or(Locale::POSIX)
+}
+
+/// The first day of the week in `locale`, from its `week` and
+/// `first_weekday` entries (a day count from a reference date, Sunday in
+/// most locales).
+fn locale_first_weekday(locale: Locale) -> Weekday {
+    let reference = pure_rust_locales::locale_match!(locale => LC_TIME::WEEK)
+        .and_then(|week| week.get(1))
+        .and_then(|&ymd| {
+            NaiveDate::from_ymd_opt(
+                (ymd / 10000) as i32,
+                (ymd / 100 % 100) as u32,
+                (ymd % 100) as u32,
+            )
+        })
+        .map_or(Weekday::Sun, |date| date.weekday());
+    let offset = pure_rust_locales::locale_match!(locale => LC_TIME::FIRST_WEEKDAY).unwrap_or(1);
+    (0..offset - 1).fold(reference, |day, _| day.succ())
+}
+
+/// The first day shown for `month`: the start of the week it begins in.
+fn grid_start(month: NaiveDate, first_weekday: Weekday) -> NaiveDate {
+    let back = month.weekday().days_since(first_weekday);
+    month - Days::new(back as u64)
+}
+
+/// A keyboard move of the selected day.
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    Days(i64),
+    Months(i32),
+}
+
+/// `date` moved by `step`. Month steps keep the day where they can and stop
+/// at the end of shorter months.
+fn shifted(date: NaiveDate, step: Step) -> NaiveDate {
+    let moved = match step {
+        Step::Days(n) if n < 0 => date.checked_sub_days(Days::new(n.unsigned_abs())),
+        Step::Days(n) => date.checked_add_days(Days::new(n as u64)),
+        Step::Months(n) if n < 0 => date.checked_sub_months(Months::new(n.unsigned_abs())),
+        Step::Months(n) => date.checked_add_months(Months::new(n as u32)),
+    };
+    moved.unwrap_or(date)
+}
+
+/// Arrow keys move by a day or a week, Page Up/Down by a month (a year with
+/// Shift), Home/End to the start and end of the week.
+fn key_step(key: gdk4::Key, shift: bool, date: NaiveDate, first_weekday: Weekday) -> Option<Step> {
+    let into_week = date.weekday().days_since(first_weekday) as i64;
+    let years = if shift { 12 } else { 1 };
+    Some(match key {
+        gdk4::Key::Left => Step::Days(-1),
+        gdk4::Key::Right => Step::Days(1),
+        gdk4::Key::Up => Step::Days(-7),
+        gdk4::Key::Down => Step::Days(7),
+        gdk4::Key::Page_Up => Step::Months(-years),
+        gdk4::Key::Page_Down => Step::Months(years),
+        gdk4::Key::Home => Step::Days(-into_week),
+        gdk4::Key::End => Step::Days(6 - into_week),
+        _ => return None,
+    })
+}
+
+/// The popover's month grid. GtkCalendar always starts its weeks on the
+/// locale's first day, so the grid is built from buttons instead; they take
+/// focus and clicks, and the keys of [`key_step`] move between days.
+struct MonthView {
+    root: gtk4::Box,
+    title: Button,
+    days: Vec<Button>,
+    locale: Locale,
+    first_weekday: Weekday,
+    /// First day of the month on show.
+    month: Cell<NaiveDate>,
+    /// The day picked by click or keyboard.
+    selected: Cell<NaiveDate>,
+}
+
+impl MonthView {
+    fn new(locale: Locale, first_weekday: Weekday) -> Rc<Self> {
+        let root = gtk4::Box::new(Orientation::Vertical, 6);
+        root.add_css_class("zenith-calendar");
+
+        let header = gtk4::Box::new(Orientation::Horizontal, 4);
+        header.add_css_class("zenith-calendar-header");
+        let prev = Button::with_label("‹");
+        prev.set_tooltip_text(Some("Previous month (Page Up)"));
+        let title = Button::new();
+        title.set_hexpand(true);
+        title.add_css_class("zenith-calendar-title");
+        title.set_tooltip_text(Some("Back to today"));
+        let next = Button::with_label("›");
+        next.set_tooltip_text(Some("Next month (Page Down)"));
+        for btn in [&prev, &title, &next] {
+            btn.add_css_class("zenith-calendar-nav");
+            btn.set_focus_on_click(false);
+            header.append(btn);
+        }
+        root.append(&header);
+
+        let grid = Grid::new();
+        grid.set_column_homogeneous(true);
+        grid.set_row_spacing(2);
+        grid.set_column_spacing(2);
+        let week = grid_start(Local::now().date_naive(), first_weekday);
+        for col in 0..7 {
+            let day = week + Days::new(col);
+            let name = Label::new(Some(&localized(day, "%a", locale)));
+            name.add_css_class("zenith-calendar-weekday");
+            grid.attach(&name, col as i32, 0, 1, 1);
+        }
+        let days: Vec<Button> = (0..WEEKS * 7)
+            .map(|i| {
+                let btn = Button::new();
+                btn.add_css_class("zenith-calendar-day");
+                grid.attach(&btn, (i % 7) as i32, (i / 7 + 1) as i32, 1, 1);
+                btn
+            })
+            .collect();
+        root.append(&grid);
+
+        let view = Rc::new(Self {
+            root,
+            title,
+            days,
+            locale,
+            first_weekday,
+            month: Cell::new(NaiveDate::MIN),
+            selected: Cell::new(NaiveDate::MIN),
+        });
+        view.show_today();
+
+        for (i, btn) in view.days.iter().enumerate() {
+            let v = Rc::downgrade(&view);
+            btn.connect_clicked(move |_| {
+                if let Some(v) = v.upgrade() {
+                    let start = grid_start(v.month.get(), v.first_weekday);
+                    v.select(start + Days::new(i as u64));
+                }
+            });
+        }
+
+        let keys = gtk4::EventControllerKey::new();
+        let v = Rc::downgrade(&view);
+        keys.connect_key_pressed(move |_, key, _, state| {
+            let Some(v) = v.upgrade() else {
+                return glib::Propagation::Proceed;
+            };
+            let shift = state.contains(gdk4::ModifierType::SHIFT_MASK);
+            let selected = v.selected.get();
+            match key_step(key, shift, selected, v.first_weekday) {
+                Some(step) => {
+                    v.select(shifted(selected, step));
+                    glib::Propagation::Stop
+                }
+                None => glib::Propagation::Proceed,
+            }
+        });
+        grid.add_controller(keys);
+
+        let v = Rc::downgrade(&view);
+        prev.connect_clicked(move |_| {
+            if let Some(v) = v.upgrade() {
+                v.show(v.month.get() - Months::new(1));
+            }
+        });
+        let v = Rc::downgrade(&view);
+        next.connect_clicked(move |_| {
+            if let Some(v) = v.upgrade() {
+                v.show(v.month.get() + Months::new(1));
+            }
+        });
+        let v = Rc::downgrade(&view);
+        view.title.connect_clicked(move |_| {
+            if let Some(v) = v.upgrade() {
+                v.show_today();
+                v.focus_selected();
+            }
+        });
+        view
+    }
+
+    fn show_today(&self) {
+        let today = Local::now().date_naive();
+        self.selected.set(today);
+        self.show(today.with_day(1).unwrap_or(today));
+    }
+
+    /// Select `day`, turning to its month if needed, and focus it.
+    fn select(&self, day: NaiveDate) {
+        self.selected.set(day);
+        self.show(day.with_day(1).unwrap_or(day));
+        self.focus_selected();
+    }
+
+    fn focus_selected(&self) {
+        let start = grid_start(self.month.get(), self.first_weekday);
+        let index = (self.selected.get() - start).num_days();
+        if let Some(btn) = usize::try_from(index).ok().and_then(|i| self.days.get(i)) {
+            btn.grab_focus();
+        }
+    }
+
+    /// Fill the grid for the month starting on `month`.
+    fn show(&self, month: NaiveDate) {
+        self.month.set(month);
+        self.title
+            .set_label(&localized(month, "%B %Y", self.locale));
+
+        let today = Local::now().date_naive();
+        let selected = self.selected.get();
+        let start = grid_start(month, self.first_weekday);
+        for (day, btn) in start.iter_days().zip(&self.days) {
+            btn.set_label(&day.day().to_string());
+            btn.update_property(&[gtk4::accessible::Property::Label(&localized(
+                day,
+                "%A %-d %B %Y",
+                self.locale,
+            ))]);
+            btn.update_state(&[gtk4::accessible::State::Selected(Some(day == selected))]);
+            for (class, on) in [
+                ("zenith-calendar-today", day == today),
+                ("zenith-calendar-selected", day == selected),
+                ("zenith-calendar-other-month", day.month() != month.month()),
+            ] {
+                if on {
+                    btn.add_css_class(class);
+                } else {
+                    btn.remove_css_class(class);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locales_pick_names_and_first_weekday() {
+        let de = parse_locale("de_DE.UTF-8").unwrap();
+        let us = parse_locale("en_US.UTF-8").unwrap();
+        assert_eq!(locale_first_weekday(de), Weekday::Mon);
+        assert_eq!(locale_first_weekday(us), Weekday::Sun);
+        assert_eq!(parse_locale("C"), Some(Locale::POSIX));
+
+        let date = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        assert_eq!(localized(date, "%d %B", de), "01 März");
+        assert_eq!(localized(date, "%Q", de), "%Q");
+    }
+
+    #[test]
+    fn grid_starts_on_the_first_weekday() {
+        // 1 Oct 2026 is a Thursday.
+        let month = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+        assert_eq!(
+            grid_start(month, Weekday::Mon),
+            NaiveDate::from_ymd_opt(2026, 9, 28).unwrap()
+        );
+        assert_eq!(
+            grid_start(month, Weekday::Sun),
+            NaiveDate::from_ymd_opt(2026, 9, 27).unwrap()
+        );
+    }
+
+    #[test]
+    fn keys_move_the_selected_day() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2026, m, d).unwrap();
+        // 31 Oct 2026 is a Saturday.
+        let from = date(10, 31);
+        let table = [
+            (gdk4::Key::Left, false, date(10, 30)),
+            (gdk4::Key::Right, false, date(11, 1)),
+            (gdk4::Key::Up, false, date(10, 24)),
+            (gdk4::Key::Down, false, date(11, 7)),
+            // No 31 November: the month step stops at its end.
+            (gdk4::Key::Page_Down, false, date(11, 30)),
+            (gdk4::Key::Page_Up, false, date(9, 30)),
+            (
+                gdk4::Key::Page_Up,
+                true,
+                NaiveDate::from_ymd_opt(2025, 10, 31).unwrap(),
+            ),
+            (gdk4::Key::Home, false, date(10, 26)),
+            (gdk4::Key::End, false, date(11, 1)),
+        ];
+        for (key, shift, expected) in table {
+            let step = key_step(key, shift, from, Weekday::Mon).unwrap();
+            assert_eq!(shifted(from, step), expected, "{key:?}");
+        }
+        assert!(key_step(gdk4::Key::a, false, from, Weekday::Mon).is_none());
+    }
 }
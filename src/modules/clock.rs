@@ -1,39 +1,354 @@
-use chrono::Local;
+use chrono::{DateTime, Local, Offset, TimeZone, Utc};
 use glib;
 use gtk4::prelude::*;
-use gtk4::Label;
-use std::cell::RefCell;
+use gtk4::{EventControllerScroll, EventControllerScrollFlags, GestureClick, Label};
+use std::cell::{Cell, RefCell};
+use std::fmt::Write;
 use std::rc::Rc;
 use std::time::Duration;
 
-/// Create a clock label that ticks every second.
+use crate::config::{ClockZone, ModulesConfig};
+use crate::timesync::TimeJumps;
+
+/// Added to every tick so it lands just after the boundary rather than a
+/// hair before it, which would show the old second again.
+const TICK_SLACK_MS: i64 = 20;
+
+/// The smallest unit a format shows, which is how often the clock ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Second,
+    Minute,
+}
+
+impl Unit {
+    /// Scan the strftime specifiers in `format`, skipping flags, widths and
+    /// the `:`/`.` modifiers (`%-S`, `%:z`, `%.3f`).
+    fn of(format: &str) -> Self {
+        let mut chars = format.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                continue;
+            }
+            let spec = chars
+                .by_ref()
+                .find(|c| !matches!(c, '-' | '_' | '0'..='9' | '^' | '#' | ':' | '.'));
+            if let Some('S' | 'T' | 'X' | 'r' | 'c' | '+' | 's' | 'f') = spec {
+                return Unit::Second;
+            }
+        }
+        Unit::Minute
+    }
+
+    fn millis(self) -> i64 {
+        match self {
+            Unit::Second => 1000,
+            Unit::Minute => 60_000,
+        }
+    }
+
+    /// Time from `now` until just after the next boundary. Every zone is a
+    /// whole number of minutes off UTC, so UTC boundaries are local ones.
+    fn until_next(self, now: DateTime<Utc>) -> Duration {
+        let into = now.timestamp_millis().rem_euclid(self.millis());
+        Duration::from_millis((self.millis() - into + TICK_SLACK_MS) as u64)
+    }
+}
+
+/// The label plus what it currently shows: `formats[format]` (or the alt
+/// format while toggled) in zone `current`, where 0 is local time and `n` is
+/// `zones[n - 1]`.
+struct Clock {
+    label: glib::WeakRef<Label>,
+    /// `clock_format` followed by `clock_formats`.
+    formats: Vec<String>,
+    alt: Option<String>,
+    tooltip: String,
+    format: Cell<usize>,
+    show_alt: Cell<bool>,
+    zones: Vec<ClockZone>,
+    current: Cell<usize>,
+    /// The pending tick, so a re-sync can replace it.
+    timer: RefCell<Option<glib::SourceId>>,
+    _jumps: Option<TimeJumps>,
+}
+
+impl Clock {
+    fn active_format(&self) -> &str {
+        match self.alt {
+            Some(ref alt) if self.show_alt.get() => alt,
+            _ => &self.formats[self.format.get()],
+        }
+    }
+
+    /// Schedule the next tick on the wall-clock boundary. The pending tick
+    /// keeps the clock alive; it stops once the label is gone.
+    fn schedule(self: &Rc<Self>) {
+        let unit = Unit::of(self.active_format());
+        let clock = Rc::clone(self);
+        let id = glib::timeout_add_local_once(unit.until_next(Utc::now()), move || {
+            clock.timer.take();
+            if clock.render() {
+                clock.schedule();
+            }
+        });
+        self.timer.replace(Some(id));
+    }
+
+    /// Drop the pending tick and start over from the current time, after
+    /// a suspend, a change of clock or time zone, or a new format.
+    fn resync(self: &Rc<Self>) {
+        if let Some(id) = self.timer.take() {
+            id.remove();
+        }
+        if self.render() {
+            self.schedule();
+        }
+    }
+
+    /// Show the current time. Returns false once the label is gone.
+    fn render(&self) -> bool {
+        let Some(label) = self.label.upgrade() else {
+            return false;
+        };
+        let now = Utc::now();
+        let format = self.active_format();
+        let text = match self.current.get().checked_sub(1).map(|i| &self.zones[i]) {
+            None => strftime(&now.with_timezone(&Local), format),
+            Some(zone) => format!(
+                "{} {}",
+                zone.label(),
+                strftime(&now.with_timezone(&zone.zone), format)
+            ),
+        };
+        label.set_label(&text);
+
+        if self.current.get() == 0 {
+            label.remove_css_class("zenith-clock-remote");
+        } else {
+            label.add_css_class("zenith-clock-remote");
+        }
+        true
+    }
+
+    /// Step to the next (or previous) zone, wrapping around.
+    fn cycle(&self, forward: bool) {
+        self.current
+            .set(step(self.current.get(), self.zones.len() + 1, forward));
+        self.render();
+    }
+
+    /// Switch between the main and the alt format.
+    fn toggle_alt(self: &Rc<Self>) {
+        self.show_alt.set(!self.show_alt.get());
+        self.resync();
+    }
+
+    /// Step to the next (or previous) format, leaving the alt format.
+    fn cycle_format(self: &Rc<Self>, forward: bool) {
+        self.show_alt.set(false);
+        self.format
+            .set(step(self.format.get(), self.formats.len(), forward));
+        self.resync();
+    }
+
+    /// The tooltip: the date lines from `clock_tooltip_format`, then the
+    /// zone table when there are zones.
+    fn tooltip_markup(&self) -> Option<String> {
+        let date = strftime(&Local::now(), &self.tooltip);
+        let mut parts = Vec::new();
+        if !date.trim().is_empty() {
+            parts.push(glib::markup_escape_text(date.trim()).to_string());
+        }
+        if !self.zones.is_empty() {
+            parts.push(self.table());
+        }
+        (!parts.is_empty()).then(|| parts.join("\n\n"))
+    }
+
+    /// Every zone's time and offset from local time, one per line.
+    fn table(&self) -> String {
+        let now = Utc::now();
+        let local = now.with_timezone(&Local);
+        let mut rows = vec![("Local".to_string(), row(&local, &local))];
+        rows.extend(
+            self.zones
+                .iter()
+                .map(|z| (z.label(), row(&now.with_timezone(&z.zone), &local))),
+        );
+        let width = rows
+            .iter()
+            .map(|(l, _)| l.chars().count())
+            .max()
+            .unwrap_or(0);
+        let lines: Vec<String> = rows
+            .into_iter()
+            .map(|(label, row)| {
+                glib::markup_escape_text(&format!("{:<width$}  {}", label, row)).to_string()
+            })
+            .collect();
+        format!("<tt>{}</tt>", lines.join("\n"))
+    }
+}
+
+/// `(index + 1) % count`, or one back when not `forward`.
+fn step(index: usize, count: usize, forward: bool) -> usize {
+    if forward {
+        (index + 1) % count
+    } else {
+        (index + count - 1) % count
+    }
+}
+
+/// `at` in `format`, or the format itself when it has a bad specifier
+/// (chrono's `to_string` would panic).
+fn strftime<Z: TimeZone>(at: &DateTime<Z>, format: &str) -> String
+where
+    Z::Offset: std::fmt::Display,
+{
+    let mut out = String::new();
+    match write!(out, "{}", at.format(format)) {
+        Ok(()) => out,
+        Err(_) => format.to_string(),
+    }
+}
+
+/// "09:12  Sun 18 Oct  -6h" for `at`, offset relative to `local`.
+fn row<Z: TimeZone>(at: &DateTime<Z>, local: &DateTime<Local>) -> String
+where
+    Z::Offset: std::fmt::Display,
+{
+    let diff = at.offset().fix().local_minus_utc() - local.offset().fix().local_minus_utc();
+    let offset = match (diff / 3600, diff.abs() % 3600 / 60) {
+        (0, 0) => String::new(),
+        (h, 0) => format!("{:+}h", h),
+        (h, m) => format!("{}{}:{:02}h", if diff < 0 { "-" } else { "+" }, h.abs(), m),
+    };
+    format!("{}  {}", at.format("%H:%M  %a %d %b"), offset)
+        .trim_end()
+        .to_string()
+}
+
+/// Create a clock label showing the current time in `clock_format`.
 ///
-/// The returned `Label` updates itself via a `glib::timeout_add_local` timer
-/// so it always shows the current time in the requested `format`.
-pub fn create(format: &str) -> Label {
+/// It ticks on each wall-clock second, or each minute when the format has no
+/// seconds, and re-syncs after a suspend or a time zone change. Clicking
+/// toggles `clock_format_alt`, scrolling steps through `clock_formats`, and
+/// the tooltip shows `clock_tooltip_format`. With `clock_zones`, the zones
+/// are listed in the tooltip and cycled by right-click (or by left-click and
+/// scrolling when those have nothing else to do).
+pub fn create(cfg: &ModulesConfig) -> Label {
     let label = Label::new(None);
     label.add_css_class("zenith-module");
     label.add_css_class("zenith-module-center");
 
+    let clock = Rc::new_cyclic(|weak: &std::rc::Weak<Clock>| {
+        let weak = weak.clone();
+        Clock {
+            label: label.downgrade(),
+            formats: std::iter::once(&cfg.clock_format)
+                .chain(&cfg.clock_formats)
+                .cloned()
+                .collect(),
+            alt: cfg.clock_format_alt.clone(),
+            tooltip: cfg.clock_tooltip_format.clone(),
+            format: Cell::new(0),
+            show_alt: Cell::new(false),
+            zones: cfg.clock_zones.clone(),
+            current: Cell::new(0),
+            timer: RefCell::new(None),
+            _jumps: TimeJumps::watch(move || {
+                if let Some(c) = weak.upgrade() {
+                    c.resync();
+                }
+            }),
+        }
+    });
+
     // Immediately show the current time so there's no blank frame.
-    let now = Local::now().format(format).to_string();
-    label.set_label(&now);
-
-    // Keep an owned copy of the format string for the closure.
-    let fmt = Rc::new(RefCell::new(format.to_owned()));
-
-    // Tick every second.
-    let weak_label = label.downgrade();
-    let fmt_clone = Rc::clone(&fmt);
-    glib::timeout_add_local(Duration::from_secs(1), move || {
-        if let Some(lbl) = weak_label.upgrade() {
-            let text = Local::now().format(&fmt_clone.borrow()).to_string();
-            lbl.set_label(&text);
-            glib::ControlFlow::Continue
-        } else {
-            glib::ControlFlow::Break
+    clock.render();
+
+    let has_zones = !clock.zones.is_empty();
+    let click = GestureClick::new();
+    click.set_button(0);
+    let c = Rc::downgrade(&clock);
+    click.connect_pressed(move |gesture, _, _, _| {
+        let Some(c) = c.upgrade() else {
+            return;
+        };
+        match gesture.current_button() {
+            3 if has_zones => c.cycle(c.alt.is_some()),
+            3 => {}
+            _ if c.alt.is_some() => c.toggle_alt(),
+            _ if has_zones => c.cycle(true),
+            _ => {}
         }
     });
+    label.add_controller(click);
+
+    if clock.formats.len() > 1 || has_zones {
+        let scroll = EventControllerScroll::new(EventControllerScrollFlags::VERTICAL);
+        let c = Rc::downgrade(&clock);
+        scroll.connect_scroll(move |_, _, dy| {
+            if let Some(c) = c.upgrade() {
+                if c.formats.len() > 1 {
+                    c.cycle_format(dy > 0.0);
+                } else {
+                    c.cycle(dy > 0.0);
+                }
+            }
+            glib::Propagation::Stop
+        });
+        label.add_controller(scroll);
+    }
+
+    label.set_has_tooltip(true);
+    let c = Rc::downgrade(&clock);
+    label.connect_query_tooltip(move |_, _, _, _, tooltip| {
+        let Some(markup) = c.upgrade().and_then(|c| c.tooltip_markup()) else {
+            return false;
+        };
+        tooltip.set_markup(Some(&markup));
+        true
+    });
+
+    clock.schedule();
 
     label
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_follows_the_format() {
+        assert_eq!(Unit::of("%H:%M"), Unit::Minute);
+        assert_eq!(Unit::of("%a %d %b  %H:%M"), Unit::Minute);
+        assert_eq!(Unit::of("%H:%M:%S"), Unit::Second);
+        assert_eq!(Unit::of("%T"), Unit::Second);
+        assert_eq!(Unit::of("%-S"), Unit::Second);
+        assert_eq!(Unit::of("%:z %%S"), Unit::Minute);
+    }
+
+    #[test]
+    fn bad_formats_do_not_panic() {
+        let at = DateTime::from_timestamp(1_760_000_000, 0).unwrap();
+        assert_eq!(strftime(&at, "%Y-%m-%d"), "2025-10-09");
+        assert_eq!(strftime(&at, "%Q"), "%Q");
+    }
+
+    #[test]
+    fn ticks_land_on_the_boundary() {
+        let now = DateTime::from_timestamp_millis(1_760_000_000_250).unwrap();
+        assert_eq!(
+            Unit::Second.until_next(now),
+            Duration::from_millis(750 + TICK_SLACK_MS as u64)
+        );
+        // 1_760_000_000 s is 20 s into its minute.
+        assert_eq!(
+            Unit::Minute.until_next(now),
+            Duration::from_millis(39_750 + TICK_SLACK_MS as u64)
+        );
+    }
+}
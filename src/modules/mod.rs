@@ -1,4 +1,5 @@
 pub mod calendar;
 pub mod clock;
+pub mod pomodoro;
 pub mod system;
 pub mod todo;
@@ -0,0 +1,483 @@
+//! Pomodoro timer: work sessions alternating with short breaks, and a long
+//! break after every few sessions.
+
+use chrono::{Duration, Local, NaiveDateTime};
+use gtk4::prelude::*;
+use gtk4::{
+    Align, Box as GtkBox, Button, DropDown, GestureClick, Label, Orientation, Popover, StringList,
+};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use crate::config::PomodoroConfig;
+use crate::modules::todo::Tasks;
+use crate::notify::Notifier;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl Phase {
+    fn minutes(self, cfg: &PomodoroConfig) -> u32 {
+        match self {
+            Phase::Work => cfg.work_minutes,
+            Phase::ShortBreak => cfg.short_break_minutes,
+            Phase::LongBreak => cfg.long_break_minutes,
+        }
+        .max(1)
+    }
+
+    fn icon(self) -> &'static str {
+        match self {
+            Phase::Work => "🍅",
+            Phase::ShortBreak => "☕",
+            Phase::LongBreak => "🌴",
+        }
+    }
+}
+
+/// Where the countdown stands. Deadlines are wall-clock times so a session
+/// keeps running through a suspend.
+struct Timer {
+    phase: Phase,
+    /// Work sessions finished since the last long break.
+    done_in_cycle: u32,
+    /// End of the current phase; `None` while paused.
+    deadline: Option<NaiveDateTime>,
+    /// Time left while paused.
+    remaining: Duration,
+}
+
+impl Timer {
+    fn new(cfg: &PomodoroConfig) -> Self {
+        Self {
+            phase: Phase::Work,
+            done_in_cycle: 0,
+            deadline: None,
+            remaining: Duration::minutes(Phase::Work.minutes(cfg) as i64),
+        }
+    }
+
+    fn left(&self, now: NaiveDateTime) -> Duration {
+        match self.deadline {
+            Some(end) => (end - now).max(Duration::zero()),
+            None => self.remaining,
+        }
+    }
+
+    fn running(&self) -> bool {
+        self.deadline.is_some()
+    }
+
+    fn toggle(&mut self, now: NaiveDateTime) {
+        match self.deadline.take() {
+            Some(end) => self.remaining = (end - now).max(Duration::zero()),
+            None => self.deadline = Some(now + self.remaining),
+        }
+    }
+
+    /// Move on to the next phase, starting it right away if `start`.
+    fn advance(&mut self, cfg: &PomodoroConfig, now: NaiveDateTime, start: bool) {
+        self.phase = match self.phase {
+            Phase::Work => {
+                self.done_in_cycle += 1;
+                if self.done_in_cycle >= cfg.long_break_every.max(1) {
+                    Phase::LongBreak
+                } else {
+                    Phase::ShortBreak
+                }
+            }
+            Phase::LongBreak => {
+                self.done_in_cycle = 0;
+                Phase::Work
+            }
+            Phase::ShortBreak => Phase::Work,
+        };
+        self.remaining = Duration::minutes(self.phase.minutes(cfg) as i64);
+        self.deadline = start.then(|| now + self.remaining);
+    }
+}
+
+struct Pomodoro {
+    cfg: PomodoroConfig,
+    timer: RefCell<Timer>,
+    tasks: Option<Tasks>,
+    /// Task credited with finished work sessions.
+    task: Cell<Option<u64>>,
+    /// Its text, looked up when the task changes rather than every tick.
+    task_text: RefCell<Option<String>>,
+    notifier: Option<Notifier>,
+    button: glib::WeakRef<Button>,
+}
+
+impl Pomodoro {
+    fn toggle(&self) {
+        let now = Local::now().naive_local();
+        let mut timer = self.timer.borrow_mut();
+        // Starting work without a task picks up the one pinned on the bar.
+        if !timer.running() && timer.phase == Phase::Work && self.task.get().is_none() {
+            self.set_task(self.tasks.as_ref().and_then(|tasks| tasks.focus()));
+        }
+        timer.toggle(now);
+        drop(timer);
+        self.render();
+    }
+
+    fn skip(&self) {
+        let now = Local::now().naive_local();
+        let mut timer = self.timer.borrow_mut();
+        let running = timer.running();
+        timer.advance(&self.cfg, now, running);
+        drop(timer);
+        self.render();
+    }
+
+    fn reset(&self) {
+        *self.timer.borrow_mut() = Timer::new(&self.cfg);
+        self.render();
+    }
+
+    fn tick(self: &Rc<Self>) {
+        let now = Local::now().naive_local();
+        let finished = {
+            let timer = self.timer.borrow();
+            (timer.running() && timer.left(now) <= Duration::zero()).then_some(timer.phase)
+        };
+        if let Some(phase) = finished {
+            self.finish(phase, now);
+        }
+        self.render();
+    }
+
+    /// The running phase ran out: credit the task and announce the next one.
+    fn finish(self: &Rc<Self>, phase: Phase, now: NaiveDateTime) {
+        if let (Phase::Work, Some(tasks), Some(id)) = (phase, &self.tasks, self.task.get()) {
+            tasks.add_pomodoro(id);
+        }
+        // Pick up a rename, or drop a task finished meanwhile.
+        self.set_task(self.task.get());
+        self.timer
+            .borrow_mut()
+            .advance(&self.cfg, now, self.cfg.auto_start);
+
+        let Some(ref notifier) = self.notifier else {
+            return;
+        };
+        let next = self.timer.borrow().phase;
+        let (summary, body) = match next {
+            Phase::Work => (
+                "Break over".to_string(),
+                match self.task_text() {
+                    Some(text) => format!("Back to: {}", text),
+                    None => "Ready for the next session.".to_string(),
+                },
+            ),
+            Phase::ShortBreak | Phase::LongBreak => (
+                "Work session done".to_string(),
+                format!("Time for a {} minute break.", next.minutes(&self.cfg)),
+            ),
+        };
+        let actions: &[(&str, &str)] = if self.cfg.auto_start {
+            &[]
+        } else {
+            &[("start", "Start")]
+        };
+        let this = Rc::downgrade(self);
+        notifier.notify(&summary, &body, actions, move |action| {
+            if let (Some(this), "start") = (this.upgrade(), action) {
+                if !this.timer.borrow().running() {
+                    this.toggle();
+                }
+            }
+        });
+    }
+
+    /// Credit sessions to task `id` from now on.
+    fn set_task(&self, id: Option<u64>) {
+        let text = id
+            .zip(self.tasks.as_ref())
+            .and_then(|(id, tasks)| tasks.text(id));
+        self.task.set(id);
+        *self.task_text.borrow_mut() = text;
+    }
+
+    fn task_text(&self) -> Option<String> {
+        self.task_text.borrow().clone()
+    }
+
+    fn render(&self) {
+        let Some(button) = self.button.upgrade() else {
+            return;
+        };
+        let timer = self.timer.borrow();
+        let left = timer.left(Local::now().naive_local());
+        let secs = left.num_seconds().max(0);
+        let clock = format!("{}:{:02}", secs / 60, secs % 60);
+
+        let every = self.cfg.long_break_every.max(1);
+        let label = match (timer.running(), timer.phase) {
+            (false, phase) => format!("⏸ {} {}", phase.icon(), clock),
+            (true, Phase::Work) => format!(
+                "{} {} · {}/{}",
+                Phase::Work.icon(),
+                clock,
+                timer.done_in_cycle + 1,
+                every
+            ),
+            (true, phase) => format!("{} {}", phase.icon(), clock),
+        };
+        button.set_label(&label);
+
+        for class in [
+            "zenith-pomodoro-work",
+            "zenith-pomodoro-break",
+            "zenith-pomodoro-paused",
+        ] {
+            button.remove_css_class(class);
+        }
+        button.add_css_class(match (timer.running(), timer.phase) {
+            (false, _) => "zenith-pomodoro-paused",
+            (true, Phase::Work) => "zenith-pomodoro-work",
+            (true, _) => "zenith-pomodoro-break",
+        });
+
+        let phase = match timer.phase {
+            Phase::Work => format!("Work · session {} of {}", timer.done_in_cycle + 1, every),
+            Phase::ShortBreak => "Short break".to_string(),
+            Phase::LongBreak => "Long break".to_string(),
+        };
+        drop(timer);
+        let task = self
+            .task_text()
+            .map(|t| format!("\nTask: {}", t))
+            .unwrap_or_default();
+        button.set_tooltip_text(Some(&format!(
+            "{}{}\nClick: start/pause · Right-click: skip, reset, task",
+            phase, task
+        )));
+    }
+}
+
+/// Create the pomodoro button. With `tasks`, finished work sessions can be
+/// credited to a task chosen from its right-click menu.
+pub fn create(cfg: &PomodoroConfig, tasks: Option<Tasks>) -> Button {
+    let button = Button::new();
+    button.add_css_class("zenith-pomodoro-btn");
+    button.add_css_class("zenith-module");
+    button.add_css_class("zenith-module-left");
+
+    let pomodoro = Rc::new(Pomodoro {
+        cfg: cfg.clone(),
+        timer: RefCell::new(Timer::new(cfg)),
+        tasks,
+        task: Cell::new(None),
+        task_text: RefCell::new(None),
+        notifier: Notifier::connect(),
+        button: button.downgrade(),
+    });
+    pomodoro.render();
+
+    // The button owns the pomodoro; everything else holds weak refs.
+    button.connect_clicked({
+        let p = Rc::clone(&pomodoro);
+        move |_| p.toggle()
+    });
+
+    // ── Right-click menu ─────────────────────────────────────────────
+    let popover = Popover::new();
+    popover.set_has_arrow(false);
+    popover.set_position(gtk4::PositionType::Bottom);
+    popover.add_css_class("zenith-todo-popup");
+    popover.set_parent(&button);
+
+    let menu = GtkBox::new(Orientation::Vertical, 6);
+    menu.add_css_class("zenith-pomodoro-menu");
+
+    let actions = GtkBox::new(Orientation::Horizontal, 6);
+    let skip_btn = Button::with_label("Skip");
+    let reset_btn = Button::with_label("Reset");
+    for btn in [&skip_btn, &reset_btn] {
+        btn.add_css_class("zenith-todo-header-btn");
+        btn.set_hexpand(true);
+        actions.append(btn);
+    }
+    menu.append(&actions);
+
+    let task_title = Label::new(Some("Count sessions on"));
+    task_title.set_halign(Align::Start);
+    task_title.add_css_class("zenith-pomodoro-menu-title");
+    let task_model = StringList::new(&["No task"]);
+    let task_dd = DropDown::new(Some(task_model.clone()), None::<gtk4::Expression>);
+    task_dd.add_css_class("zenith-todo-filter");
+    if pomodoro.tasks.is_some() {
+        menu.append(&task_title);
+        menu.append(&task_dd);
+    }
+    popover.set_child(Some(&menu));
+
+    // Ids behind the dropdown entries after "No task".
+    let task_ids: Rc<RefCell<Vec<u64>>> = Rc::new(RefCell::new(Vec::new()));
+    // Set while the model is rebuilt so the selection change is ignored.
+    let filling = Rc::new(Cell::new(false));
+
+    let right_click = GestureClick::new();
+    right_click.set_button(3);
+    right_click.connect_pressed({
+        let p = Rc::downgrade(&pomodoro);
+        let popover = popover.clone();
+        let task_ids = Rc::clone(&task_ids);
+        let filling = Rc::clone(&filling);
+        let task_dd = task_dd.clone();
+        move |_, _, _, _| {
+            let Some(p) = p.upgrade() else {
+                return;
+            };
+            if let Some(ref tasks) = p.tasks {
+                let pending = tasks.pending();
+                let mut labels = vec!["No task".to_string()];
+                labels.extend(pending.iter().map(|(_, text)| text.clone()));
+                let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+                let selected = p
+                    .task
+                    .get()
+                    .and_then(|id| pending.iter().position(|(t, _)| *t == id))
+                    .map_or(0, |i| i + 1);
+
+                filling.set(true);
+                task_model.splice(0, task_model.n_items(), &labels);
+                task_dd.set_selected(selected as u32);
+                filling.set(false);
+                *task_ids.borrow_mut() = pending.into_iter().map(|(id, _)| id).collect();
+            }
+            popover.popup();
+        }
+    });
+    button.add_controller(right_click);
+
+    skip_btn.connect_clicked({
+        let p = Rc::downgrade(&pomodoro);
+        let popover = popover.clone();
+        move |_| {
+            if let Some(p) = p.upgrade() {
+                p.skip();
+            }
+            popover.popdown();
+        }
+    });
+    reset_btn.connect_clicked({
+        let p = Rc::downgrade(&pomodoro);
+        let popover = popover.clone();
+        move |_| {
+            if let Some(p) = p.upgrade() {
+                p.reset();
+            }
+            popover.popdown();
+        }
+    });
+    task_dd.connect_selected_notify({
+        let p = Rc::downgrade(&pomodoro);
+        move |dd| {
+            if filling.get() {
+                return;
+            }
+            if let Some(p) = p.upgrade() {
+                let id = (dd.selected() as usize)
+                    .checked_sub(1)
+                    .and_then(|i| task_ids.borrow().get(i).copied());
+                p.set_task(id);
+                p.render();
+            }
+        }
+    });
+
+    // ── Countdown ────────────────────────────────────────────────────
+    let p = Rc::downgrade(&pomodoro);
+    glib::timeout_add_seconds_local(1, move || match p.upgrade() {
+        Some(p) => {
+            p.tick();
+            glib::ControlFlow::Continue
+        }
+        None => glib::ControlFlow::Break,
+    });
+
+    button
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn cfg() -> PomodoroConfig {
+        PomodoroConfig {
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            long_break_every: 3,
+            auto_start: true,
+        }
+    }
+
+    fn at(h: u32, m: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 19)
+            .unwrap()
+            .and_hms_opt(h, m, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn long_break_follows_every_nth_work_session() {
+        let cfg = cfg();
+        let mut timer = Timer::new(&cfg);
+        let mut phases = Vec::new();
+        for _ in 0..8 {
+            timer.advance(&cfg, at(9, 0), true);
+            phases.push(timer.phase);
+        }
+        use Phase::*;
+        assert_eq!(
+            phases,
+            [ShortBreak, Work, ShortBreak, Work, LongBreak, Work, ShortBreak, Work]
+        );
+        assert_eq!(timer.left(at(9, 0)), Duration::minutes(25));
+    }
+
+    #[test]
+    fn pausing_keeps_the_time_left() {
+        let cfg = cfg();
+        let mut timer = Timer::new(&cfg);
+        assert!(!timer.running());
+
+        timer.toggle(at(9, 0));
+        assert!(timer.running());
+        assert_eq!(timer.left(at(9, 10)), Duration::minutes(15));
+
+        timer.toggle(at(9, 10));
+        assert!(!timer.running());
+        assert_eq!(timer.left(at(11, 0)), Duration::minutes(15));
+
+        timer.toggle(at(11, 0));
+        assert_eq!(timer.left(at(11, 15)), Duration::zero());
+        assert_eq!(timer.left(at(12, 0)), Duration::zero());
+    }
+
+    #[test]
+    fn skipping_moves_on_without_starting_a_paused_timer() {
+        let cfg = cfg();
+        let mut timer = Timer::new(&cfg);
+        timer.advance(&cfg, at(9, 0), false);
+        assert_eq!(timer.phase, Phase::ShortBreak);
+        assert!(!timer.running());
+        assert_eq!(timer.left(at(9, 30)), Duration::minutes(5));
+
+        timer.toggle(at(9, 30));
+        timer.advance(&cfg, at(9, 32), true);
+        assert_eq!(timer.phase, Phase::Work);
+        assert_eq!(timer.left(at(9, 32)), Duration::minutes(25));
+        // A skipped break does not count as a work session.
+        assert_eq!(timer.done_in_cycle, 1);
+    }
+}
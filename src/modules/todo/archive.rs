@@ -0,0 +1,165 @@
+use anyhow::{Context, Result};
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use super::undo::ArchiveMove;
+use super::{backend, TodoItem, TodoStore};
+use crate::config::TodoConfig;
+
+/// Completed tasks moved out of the live list, newest last.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Archive {
+    pub items: Vec<Entry>,
+}
+
+/// An archived task and the list it was archived from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    #[serde(flatten)]
+    pub item: TodoItem,
+    /// Name of the list the task came from; entries archived before lists
+    /// were recorded have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_list: Option<String>,
+}
+
+impl Archive {
+    /// Path: $XDG_DATA_HOME/zenith/archive.json, whatever the list's format.
+    fn storage_path() -> PathBuf {
+        backend::data_file("archive.json")
+    }
+
+    /// Read the archive; a missing file is an empty archive. A file that
+    /// cannot be read or parsed is an error, so it is never overwritten.
+    pub fn load() -> Result<Self> {
+        let path = Self::storage_path();
+        let Some(raw) = backend::read_file(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+        else {
+            return Ok(Self::default());
+        };
+        serde_json::from_str(&raw).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// The archive for read-only views. A broken file is logged and shows
+    /// as empty.
+    pub fn load_or_empty() -> Self {
+        Self::load().unwrap_or_else(|e| {
+            log::error!("{e:#}");
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::storage_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// The archived tasks, oldest first.
+    pub fn tasks(&self) -> impl DoubleEndedIterator<Item = &TodoItem> {
+        self.items.iter().map(|e| &e.item)
+    }
+
+    /// Append `items` to the archive file, replacing earlier copies of the
+    /// same entries. `lists` names the lists the items' indices refer to.
+    pub fn append(items: Vec<TodoItem>, lists: &[&str]) -> Result<()> {
+        let change = ArchiveMove {
+            archived: items,
+            restored: Vec::new(),
+        };
+        Self::apply(&change, lists)
+    }
+
+    /// Write `change` to the archive file: add what it archived and drop
+    /// what it restored.
+    pub fn apply(change: &ArchiveMove, lists: &[&str]) -> Result<()> {
+        if change.is_empty() {
+            return Ok(());
+        }
+        let mut archive = Self::load()?;
+        archive.items.retain(|a| {
+            !change
+                .archived
+                .iter()
+                .chain(&change.restored)
+                .any(|t| same_entry(&a.item, t))
+        });
+        archive
+            .items
+            .extend(change.archived.iter().map(|item| Entry {
+                from_list: lists.get(item.list).map(|name| name.to_string()),
+                item: item.clone(),
+            }));
+        archive.save()
+    }
+
+    /// Drop archived entries that are back in the live list (e.g. after an
+    /// undo that could not update the file), so a task is never in both
+    /// places. Returns whether any were dropped.
+    pub fn prune(&mut self, store: &TodoStore) -> bool {
+        let before = self.items.len();
+        self.items
+            .retain(|a| !store.items.iter().any(|t| same_entry(&a.item, t)));
+        self.items.len() != before
+    }
+
+    /// Take the entry matching `id` and completion time out of the archive.
+    pub fn take(&mut self, id: u64, completed_at: Option<NaiveDateTime>) -> Option<Entry> {
+        let pos = self
+            .items
+            .iter()
+            .position(|a| a.item.id == id && a.item.completed_at == completed_at)?;
+        Some(self.items.remove(pos))
+    }
+}
+
+/// Ids are reused once the highest one leaves the list, so the completion
+/// time tells an archived task apart from a newer one with the same id.
+fn same_entry(a: &TodoItem, b: &TodoItem) -> bool {
+    a.id == b.id && a.done == b.done && a.completed_at == b.completed_at
+}
+
+/// Whether a completed item is old enough to be archived automatically.
+pub fn is_expired(item: &TodoItem, cfg: &TodoConfig, now: NaiveDateTime) -> bool {
+    let Some(done_at) = item.completed_at.filter(|_| item.done) else {
+        return false;
+    };
+    let by_age = cfg
+        .archive_after_hours
+        .is_some_and(|h| now - done_at >= Duration::hours(h as i64));
+    let by_midnight = cfg.archive_at_midnight && done_at.date() < now.date();
+    by_age || by_midnight
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_record_their_list_and_older_ones_still_load() {
+        let entry = Entry {
+            item: TodoItem {
+                id: 7,
+                text: "Ship".into(),
+                done: true,
+                ..Default::default()
+            },
+            from_list: Some("work".into()),
+        };
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["text"], "Ship");
+        assert_eq!(json["from_list"], "work");
+
+        let old: Archive =
+            serde_json::from_str(r#"{"items":[{"id":3,"text":"Old","done":true,"priority":0}]}"#)
+                .unwrap();
+        assert_eq!(old.items[0].item.text, "Old");
+        assert_eq!(old.items[0].from_list, None);
+    }
+}
@@ -0,0 +1,379 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::taskwarrior::{self, TaskwarriorBackend};
+use super::{todotxt, TodoItem};
+use crate::config::{StorageFormat, TodoConfig};
+
+/// Somewhere the task list can be read from and written back to.
+pub trait TodoBackend {
+    fn load(&self) -> Result<Vec<TodoItem>>;
+    /// Write `items` back. Backends that assign their own identifiers record
+    /// them in [`TodoItem::external_id`].
+    fn save(&self, items: &mut [TodoItem]) -> Result<()>;
+    /// File or directory the backend reads and writes, for messages.
+    fn path(&self) -> &Path;
+    /// Backends that load in the background call `f` once a fresh list is
+    /// in; the next `load` returns it.
+    fn on_loaded(&self, _f: Box<dyn Fn()>) {}
+}
+
+/// Open the backend described by `format` at `path`, or at the format's
+/// default location when `path` is `None`.
+pub fn open(format: StorageFormat, path: Option<&Path>) -> Box<dyn TodoBackend> {
+    let path = match path {
+        Some(p) => p.to_path_buf(),
+        None => default_path(format),
+    };
+    match format {
+        StorageFormat::Json => Box::new(JsonBackend { path }),
+        StorageFormat::TodoTxt => Box::new(TodoTxtBackend { path }),
+        StorageFormat::Sqlite => Box::new(SqliteBackend { path }),
+        StorageFormat::Taskwarrior => Box::new(TaskwarriorBackend::new(path)),
+    }
+}
+
+/// The lists configured in the `[todo]` section, each with its backend.
+/// Without `lists` there is a single list called "Tasks".
+pub fn lists_from_config(cfg: &TodoConfig) -> Vec<(String, Box<dyn TodoBackend>)> {
+    let base = cfg.path.clone().unwrap_or_else(|| default_path(cfg.format));
+    if cfg.lists.is_empty() {
+        return vec![("Tasks".to_string(), open(cfg.format, Some(&base)))];
+    }
+    cfg.lists
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let path = match i {
+                0 => base.clone(),
+                _ => list_path(&base, name),
+            };
+            (name.clone(), open(cfg.format, Some(&path)))
+        })
+        .collect()
+}
+
+/// One list's source and target in a migration.
+pub struct ListMigration {
+    pub name: String,
+    pub source: Box<dyn TodoBackend>,
+    pub target: Box<dyn TodoBackend>,
+}
+
+/// What to copy for moving every list in `cfg` from `from` to `to`. Paths of
+/// `None` mean the format's default location; each further list sits next
+/// to its first list as in [`lists_from_config`].
+pub fn migration(
+    cfg: &TodoConfig,
+    from: (StorageFormat, Option<PathBuf>),
+    to: (StorageFormat, Option<PathBuf>),
+) -> Vec<ListMigration> {
+    let with = |(format, path)| TodoConfig {
+        format,
+        path,
+        ..cfg.clone()
+    };
+    lists_from_config(&with(from))
+        .into_iter()
+        .zip(lists_from_config(&with(to)))
+        .map(|((name, source), (_, target))| ListMigration {
+            name,
+            source,
+            target,
+        })
+        .collect()
+}
+
+/// Where the list `name` is stored next to `base`: `todos.json` becomes
+/// `todos-<name>.json`, lowercased with anything unusual replaced by `-`.
+pub fn list_path(base: &Path, name: &str) -> PathBuf {
+    let slug: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    let stem = base.file_stem().unwrap_or_default().to_string_lossy();
+    let file = match base.extension() {
+        Some(ext) => format!("{}-{}.{}", stem, slug, ext.to_string_lossy()),
+        None => format!("{}-{}", stem, slug),
+    };
+    base.with_file_name(file)
+}
+
+/// Default location for `format`: `$XDG_DATA_HOME/zenith/<file>`, or the
+/// Taskwarrior data directory.
+pub fn default_path(format: StorageFormat) -> PathBuf {
+    let file = match format {
+        StorageFormat::Json => "todos.json",
+        StorageFormat::TodoTxt => "todo.txt",
+        StorageFormat::Sqlite => "todos.db",
+        StorageFormat::Taskwarrior => return taskwarrior::default_data_dir(),
+    };
+    data_file(file)
+}
+
+/// `$XDG_DATA_HOME/zenith/<name>`.
+pub fn data_file(name: &str) -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("zenith")
+        .join(name)
+}
+
+/// The task list as older versions kept it, under `~/.config/zenith`.
+const LEGACY_FILE: &str = "todos.json";
+
+/// Move a task list left in `~/.config/zenith` by an older version to
+/// [`data_file`]. Runs once at startup, before anything opens the list. A
+/// failed move is an error, so the list never silently shows up empty.
+pub fn move_legacy_data(cfg: &TodoConfig) -> Result<()> {
+    let Some(legacy) = dirs::config_dir().map(|d| d.join("zenith").join(LEGACY_FILE)) else {
+        return Ok(());
+    };
+    let path = data_file(LEGACY_FILE);
+    // A `path` set in the config may still point at the old file.
+    if path.exists() || !legacy.exists() || cfg.path.as_ref() == Some(&legacy) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(&legacy, &path)
+        .with_context(|| format!("Failed to move {} to {}", legacy.display(), path.display()))?;
+    log::info!("Moved {} to {}", legacy.display(), path.display());
+    Ok(())
+}
+
+/// Copy every item from one backend to another, replacing the target's
+/// contents. Returns the number of items copied.
+pub fn migrate(from: &dyn TodoBackend, to: &dyn TodoBackend) -> Result<usize> {
+    let mut items = from
+        .load()
+        .with_context(|| format!("Failed to read {}", from.path().display()))?;
+    to.save(&mut items)
+        .with_context(|| format!("Failed to write {}", to.path().display()))?;
+    Ok(items.len())
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+/// A missing file is an empty list rather than an error.
+pub(super) fn read_file(path: &Path) -> Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(s) => Ok(Some(s)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// ─── JSON ────────────────────────────────────────────────────────────────────
+
+/// Zenith's native format: `{ "items": [...] }`.
+pub struct JsonBackend {
+    path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct JsonFile {
+    items: Vec<TodoItem>,
+}
+
+impl TodoBackend for JsonBackend {
+    fn load(&self) -> Result<Vec<TodoItem>> {
+        let Some(raw) = read_file(&self.path)? else {
+            return Ok(Vec::new());
+        };
+        let file: JsonFile = serde_json::from_str(&raw)?;
+        Ok(file.items)
+    }
+
+    fn save(&self, items: &mut [TodoItem]) -> Result<()> {
+        let file = JsonFile {
+            items: items.to_vec(),
+        };
+        write_file(&self.path, &serde_json::to_string_pretty(&file)?)
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+// ─── todo.txt ────────────────────────────────────────────────────────────────
+
+/// Plain-text `todo.txt`; see [`todotxt`] for the field mapping.
+pub struct TodoTxtBackend {
+    path: PathBuf,
+}
+
+impl TodoBackend for TodoTxtBackend {
+    fn load(&self) -> Result<Vec<TodoItem>> {
+        Ok(read_file(&self.path)?
+            .map(|s| todotxt::parse(&s))
+            .unwrap_or_default())
+    }
+
+    fn save(&self, items: &mut [TodoItem]) -> Result<()> {
+        write_file(&self.path, &todotxt::serialize(items))
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+// ─── SQLite ──────────────────────────────────────────────────────────────────
+
+/// One row per task. The full item is stored as JSON in `data`; the other
+/// columns duplicate the common fields so the database can be queried with
+/// ordinary SQL.
+pub struct SqliteBackend {
+    path: PathBuf,
+}
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS todos (
+        position INTEGER PRIMARY KEY,
+        id       INTEGER NOT NULL,
+        text     TEXT    NOT NULL,
+        done     INTEGER NOT NULL,
+        priority INTEGER NOT NULL,
+        data     TEXT    NOT NULL
+    );
+";
+
+impl SqliteBackend {
+    fn connect(&self) -> Result<Connection> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(&self.path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(conn)
+    }
+}
+
+impl TodoBackend for SqliteBackend {
+    fn load(&self) -> Result<Vec<TodoItem>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare("SELECT data FROM todos ORDER BY position")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.map(|data| Ok(serde_json::from_str(&data?)?)).collect()
+    }
+
+    fn save(&self, items: &mut [TodoItem]) -> Result<()> {
+        let mut conn = self.connect()?;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM todos", [])?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO todos (position, id, text, done, priority, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for (position, item) in items.iter().enumerate() {
+                insert.execute(params![
+                    position as i64,
+                    item.id as i64,
+                    item.text,
+                    item.done,
+                    item.priority,
+                    serde_json::to_string(item)?,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::todo::TodoStore;
+
+    #[test]
+    fn list_files_sit_next_to_the_first() {
+        let base = Path::new("/data/zenith/todos.json");
+        assert_eq!(
+            list_path(base, "Sprint 12"),
+            Path::new("/data/zenith/todos-sprint-12.json")
+        );
+        assert_eq!(
+            list_path(Path::new("/home/me/.task"), "Home"),
+            Path::new("/home/me/.task-home")
+        );
+    }
+
+    #[test]
+    fn lists_are_stored_separately() {
+        let dir = std::env::temp_dir().join(format!("zenith-lists-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let cfg = TodoConfig {
+            path: Some(dir.join("todos.json")),
+            lists: vec!["Work".into(), "Home".into()],
+            default_list: Some("home".into()),
+            ..Default::default()
+        };
+        let now = chrono::Local::now().naive_local();
+
+        let mut store = TodoStore::open(&cfg);
+        assert_eq!(store.active, 1);
+        let groceries = store.add("Buy milk", now);
+        store.active = 0;
+        let report = store.add("Write report", now);
+        assert!(store.move_to_list(report, 1));
+        store.save();
+
+        let store = TodoStore::open(&cfg);
+        let home: Vec<&str> = store.active_items().map(|t| t.text.as_str()).collect();
+        assert_eq!(home, ["Buy milk", "Write report"]);
+        assert!(store.find(groceries).is_some());
+        assert!(dir.join("todos-home.json").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn migration_covers_every_list() {
+        let dir = std::env::temp_dir().join(format!("zenith-migrate-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let cfg = TodoConfig {
+            path: Some(dir.join("todos.json")),
+            lists: vec!["Work".into(), "Home".into()],
+            ..Default::default()
+        };
+        let now = chrono::Local::now().naive_local();
+        let mut store = TodoStore::open(&cfg);
+        store.add("Write report", now);
+        store.active = 1;
+        store.add("Buy milk", now);
+        store.save();
+
+        let to = (StorageFormat::TodoTxt, Some(dir.join("todo.txt")));
+        let lists = migration(&cfg, (cfg.format, cfg.path.clone()), to);
+        assert_eq!(lists.len(), 2);
+        for list in &lists {
+            migrate(list.source.as_ref(), list.target.as_ref()).unwrap();
+        }
+        let home = fs::read_to_string(dir.join("todo-home.txt")).unwrap();
+        assert!(home.contains("Buy milk"), "{home}");
+        let work = fs::read_to_string(dir.join("todo.txt")).unwrap();
+        assert!(work.contains("Write report") && !work.contains("Buy milk"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
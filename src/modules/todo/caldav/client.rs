@@ -0,0 +1,265 @@
+//! Minimal blocking WebDAV/CalDAV client for one task collection.
+
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+use std::fmt;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+/// `calendar-query` REPORT for every VTODO with its ETag and data.
+const REPORT_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop>
+    <d:getetag/>
+    <c:calendar-data/>
+  </d:prop>
+  <c:filter>
+    <c:comp-filter name="VCALENDAR">
+      <c:comp-filter name="VTODO"/>
+    </c:comp-filter>
+  </c:filter>
+</c:calendar-query>"#;
+
+const DAV_NS: &str = "DAV:";
+const CALDAV_NS: &str = "urn:ietf:params:xml:ns:caldav";
+
+/// The server could not be reached; changes stay queued until it can.
+#[derive(Debug)]
+pub struct Offline(pub String);
+
+impl fmt::Display for Offline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "server unreachable: {}", self.0)
+    }
+}
+
+impl std::error::Error for Offline {}
+
+/// A task resource on the server.
+#[derive(Debug, Clone)]
+pub struct Resource {
+    /// Absolute URL of the `.ics` file.
+    pub href: String,
+    pub etag: Option<String>,
+    pub ics: String,
+}
+
+/// Result of a conditional write.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Written {
+    /// Stored; carries the new ETag when the server sent one.
+    Ok(Option<String>),
+    /// The resource changed on the server since we last saw it (HTTP 412).
+    Conflict,
+}
+
+pub struct DavClient {
+    agent: ureq::Agent,
+    /// Collection URL, always ending in `/`.
+    url: String,
+    auth: Option<String>,
+}
+
+impl DavClient {
+    pub fn new(url: &str, username: &str, password: Option<&str>) -> Self {
+        let url = if url.ends_with('/') {
+            url.to_string()
+        } else {
+            format!("{}/", url)
+        };
+        let auth = (!username.is_empty()).then(|| {
+            let creds = format!("{}:{}", username, password.unwrap_or_default());
+            format!(
+                "Basic {}",
+                base64::engine::general_purpose::STANDARD.encode(creds)
+            )
+        });
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(TIMEOUT).build(),
+            url,
+            auth,
+        }
+    }
+
+    /// URL for a new resource named after `uid`.
+    pub fn href_for(&self, uid: &str) -> String {
+        let safe: String = uid
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || "-_.@".contains(c) {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{}{}.ics", self.url, safe)
+    }
+
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        let req = self.agent.request(method, url);
+        match self.auth {
+            Some(ref auth) => req.set("Authorization", auth),
+            None => req,
+        }
+    }
+
+    /// Every VTODO in the collection.
+    pub fn list(&self) -> Result<Vec<Resource>> {
+        let resp = self
+            .request("REPORT", &self.url)
+            .set("Depth", "1")
+            .set("Content-Type", "application/xml; charset=utf-8")
+            .send_string(REPORT_BODY)
+            .map_err(classify)?;
+        let body = resp
+            .into_string()
+            .context("Failed to read REPORT response")?;
+        self.parse_multistatus(&body)
+    }
+
+    fn parse_multistatus(&self, body: &str) -> Result<Vec<Resource>> {
+        let doc = roxmltree::Document::parse(body).context("Malformed REPORT response")?;
+        let child_text = |node: roxmltree::Node, ns: &str, name: &str| {
+            node.descendants()
+                .find(|n| n.has_tag_name((ns, name)))
+                .and_then(|n| n.text())
+                .map(|t| t.trim().to_string())
+        };
+
+        Ok(doc
+            .descendants()
+            .filter(|n| n.has_tag_name((DAV_NS, "response")))
+            .filter_map(|resp| {
+                let href = child_text(resp, DAV_NS, "href")?;
+                let ics = child_text(resp, CALDAV_NS, "calendar-data")?;
+                Some(Resource {
+                    href: self.resolve(&href),
+                    etag: child_text(resp, DAV_NS, "getetag"),
+                    ics,
+                })
+            })
+            .collect())
+    }
+
+    /// Make a server-relative href absolute.
+    fn resolve(&self, href: &str) -> String {
+        if href.contains("://") {
+            return href.to_string();
+        }
+        let origin_end = self
+            .url
+            .find("://")
+            .and_then(|i| self.url[i + 3..].find('/').map(|j| i + 3 + j))
+            .unwrap_or(self.url.len());
+        if href.starts_with('/') {
+            format!("{}{}", &self.url[..origin_end], href)
+        } else {
+            format!("{}{}", self.url, href)
+        }
+    }
+
+    /// Create (`etag = None`) or update a resource, guarded by its ETag.
+    pub fn put(&self, href: &str, ics: &str, etag: Option<&str>) -> Result<Written> {
+        let req = self
+            .request("PUT", href)
+            .set("Content-Type", "text/calendar; charset=utf-8");
+        let req = match etag {
+            Some(tag) => req.set("If-Match", tag),
+            None => req.set("If-None-Match", "*"),
+        };
+        match req.send_string(ics) {
+            Ok(resp) => Ok(Written::Ok(resp.header("ETag").map(str::to_string))),
+            Err(ureq::Error::Status(412, _)) => Ok(Written::Conflict),
+            Err(e) => Err(classify(e)),
+        }
+    }
+
+    /// Delete a resource unless it changed since `etag`.
+    pub fn delete(&self, href: &str, etag: Option<&str>) -> Result<Written> {
+        let req = self.request("DELETE", href);
+        let req = match etag {
+            Some(tag) => req.set("If-Match", tag),
+            None => req,
+        };
+        match req.call() {
+            Ok(_) | Err(ureq::Error::Status(404, _)) => Ok(Written::Ok(None)),
+            Err(ureq::Error::Status(412, _)) => Ok(Written::Conflict),
+            Err(e) => Err(classify(e)),
+        }
+    }
+}
+
+/// Tell "can't reach the server" apart from errors the server reported.
+fn classify(err: ureq::Error) -> anyhow::Error {
+    match err {
+        ureq::Error::Transport(t) => Offline(t.to_string()).into(),
+        ureq::Error::Status(code, resp) => {
+            anyhow!("server returned {} {}", code, resp.status_text())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multistatus_is_parsed_and_hrefs_resolved() {
+        let client = DavClient::new("http://localhost:5232/user/tasks", "", None);
+        let body = r#"<?xml version="1.0"?>
+            <multistatus xmlns="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+              <response>
+                <href>/user/tasks/a.ics</href>
+                <propstat><prop>
+                  <getetag>"e1"</getetag>
+                  <C:calendar-data>BEGIN:VCALENDAR
+END:VCALENDAR</C:calendar-data>
+                </prop><status>HTTP/1.1 200 OK</status></propstat>
+              </response>
+              <response>
+                <href>/user/tasks/</href>
+                <propstat><prop/><status>HTTP/1.1 404 Not Found</status></propstat>
+              </response>
+            </multistatus>"#;
+        let resources = client.parse_multistatus(body).unwrap();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].href, "http://localhost:5232/user/tasks/a.ics");
+        assert_eq!(resources[0].etag.as_deref(), Some("\"e1\""));
+        assert!(resources[0].ics.starts_with("BEGIN:VCALENDAR"));
+    }
+
+    /// Against a local Radicale (`python -m radicale --storage-filesystem-folder
+    /// /tmp/dav --auth-type none`) with a task list created at the URL in
+    /// `ZENITH_CALDAV_URL`: `cargo test -- --ignored`.
+    #[test]
+    #[ignore]
+    fn put_list_and_delete_against_a_live_server() {
+        let url = std::env::var("ZENITH_CALDAV_URL").expect("ZENITH_CALDAV_URL not set");
+        let client = DavClient::new(&url, "zenith", Some("zenith"));
+        let uid = format!("zenith-test-{}", std::process::id());
+        let href = client.href_for(&uid);
+        let ics = format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Zenith//Test//EN\r\n\
+             BEGIN:VTODO\r\nUID:{uid}\r\nDTSTAMP:20261018T120000Z\r\nSUMMARY:Test\r\n\
+             END:VTODO\r\nEND:VCALENDAR\r\n"
+        );
+
+        let Written::Ok(_) = client.put(&href, &ics, None).unwrap() else {
+            panic!("create failed");
+        };
+        assert_eq!(client.put(&href, &ics, None).unwrap(), Written::Conflict);
+
+        let listed = client.list().unwrap();
+        let res = listed.iter().find(|r| r.href == href).expect("listed");
+        assert_eq!(
+            client.put(&href, &ics, Some("\"stale\"")).unwrap(),
+            Written::Conflict
+        );
+        assert!(matches!(
+            client.delete(&href, res.etag.as_deref()).unwrap(),
+            Written::Ok(_)
+        ));
+    }
+}
@@ -0,0 +1,386 @@
+//! Just enough iCalendar (RFC 5545) to read and write VTODO components.
+//!
+//! Writing starts from the server's copy when there is one and only replaces
+//! the properties Zenith manages, so alarms, recurrence rules and other
+//! clients' extensions survive an edit.
+
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+use crate::modules::todo::{Due, TodoItem};
+
+/// Properties of a VTODO that [`write_vtodo`] regenerates.
+const MANAGED: [&str; 11] = [
+    "UID",
+    "DTSTAMP",
+    "LAST-MODIFIED",
+    "SUMMARY",
+    "STATUS",
+    "PRIORITY",
+    "DUE",
+    "COMPLETED",
+    "CREATED",
+    "CATEGORIES",
+    "X-ZENITH-PROJECT",
+];
+
+/// One unfolded content line: `NAME;PARAMS:VALUE`.
+struct Line<'a> {
+    name: String,
+    params: &'a str,
+    value: &'a str,
+}
+
+fn parse_line(line: &str) -> Option<Line<'_>> {
+    let colon = line.find(':')?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some(Line {
+        name: name.to_ascii_uppercase(),
+        params,
+        value,
+    })
+}
+
+/// Undo line folding: a line starting with a space or tab continues the
+/// previous one.
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in ics.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(cont), Some(last)) => last.push_str(cont),
+            _ if raw.is_empty() => {}
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// Fold a content line to 75 octets, breaking on character boundaries.
+fn fold(line: &str, out: &mut String) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Split a list value on unescaped commas.
+fn split_list(value: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut escaped = false;
+    for c in value.chars() {
+        match c {
+            ',' if !escaped => parts.push(String::new()),
+            _ => {
+                if let Some(last) = parts.last_mut() {
+                    last.push(c);
+                }
+            }
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    parts
+        .iter()
+        .map(|p| unescape(p.trim()))
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// A DATE or DATE-TIME value. UTC times are converted to local time; times
+/// with a TZID are read as local time.
+fn parse_time(params: &str, value: &str) -> Option<(NaiveDateTime, bool)> {
+    if params.to_ascii_uppercase().contains("VALUE=DATE") && !value.contains('T') {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some((date.and_hms_opt(0, 0, 0)?, true));
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let at = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        let local = Utc.from_utc_datetime(&at).with_timezone(&Local);
+        return Some((local.naive_local(), false));
+    }
+    if let Ok(at) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        return Some((at, false));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+    Some((date.and_hms_opt(0, 0, 0)?, true))
+}
+
+fn utc_stamp(at: NaiveDateTime) -> String {
+    Local
+        .from_local_datetime(&at)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc).naive_utc())
+        .unwrap_or(at)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Read the first VTODO in `ics`. The UID becomes [`TodoItem::external_id`].
+pub fn parse_vtodo(ics: &str) -> Option<TodoItem> {
+    let mut item = TodoItem::default();
+    let mut depth = 0;
+    let mut found = false;
+
+    for raw in unfold(ics) {
+        let Some(line) = parse_line(&raw) else {
+            continue;
+        };
+        match (line.name.as_str(), line.value.to_ascii_uppercase().as_str()) {
+            ("BEGIN", "VTODO") if !found => {
+                found = true;
+                depth = 1;
+                continue;
+            }
+            ("BEGIN", _) if depth > 0 => depth += 1,
+            ("END", _) if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            _ => {}
+        }
+        // Only the VTODO's own properties, not those of nested alarms.
+        if depth != 1 {
+            continue;
+        }
+
+        match line.name.as_str() {
+            "UID" => item.external_id = Some(line.value.to_string()),
+            "SUMMARY" => item.text = unescape(line.value),
+            "STATUS" => item.done = line.value.eq_ignore_ascii_case("COMPLETED"),
+            "PRIORITY" => item.priority = line.value.trim().parse::<u8>().unwrap_or(0).min(9),
+            "DUE" => {
+                item.due = parse_time(line.params, line.value).map(|(at, all_day)| Due {
+                    date: at.date(),
+                    time: (!all_day).then_some(at.time()),
+                })
+            }
+            "COMPLETED" => item.completed_at = parse_time(line.params, line.value).map(|t| t.0),
+            "CREATED" => item.created = parse_time(line.params, line.value).map(|t| t.0),
+            "CATEGORIES" => item.tags.extend(split_list(line.value)),
+            "X-ZENITH-PROJECT" => item.project = Some(unescape(line.value)),
+            _ => {}
+        }
+    }
+
+    if !item.done {
+        item.completed_at = None;
+    }
+    (found && item.external_id.is_some()).then_some(item)
+}
+
+/// Serialise `item` (which must carry a UID in `external_id`) as a
+/// VCALENDAR. With `previous`, everything Zenith does not manage is copied
+/// over from it.
+pub fn write_vtodo(item: &TodoItem, previous: Option<&str>, now: NaiveDateTime) -> String {
+    let mut props = Vec::new();
+    let uid = item.external_id.as_deref().unwrap_or_default();
+    props.push(format!("UID:{}", uid));
+    props.push(format!("DTSTAMP:{}", utc_stamp(now)));
+    props.push(format!("LAST-MODIFIED:{}", utc_stamp(now)));
+    props.push(format!("SUMMARY:{}", escape(&item.text)));
+    props.push(format!(
+        "STATUS:{}",
+        if item.done {
+            "COMPLETED"
+        } else {
+            "NEEDS-ACTION"
+        }
+    ));
+    if item.priority > 0 {
+        props.push(format!("PRIORITY:{}", item.priority));
+    }
+    if let Some(due) = item.due {
+        props.push(match due.time {
+            Some(t) => format!("DUE:{}", due.date.and_time(t).format("%Y%m%dT%H%M%S")),
+            None => format!("DUE;VALUE=DATE:{}", due.date.format("%Y%m%d")),
+        });
+    }
+    if let (true, Some(at)) = (item.done, item.completed_at) {
+        props.push(format!("COMPLETED:{}", utc_stamp(at)));
+    }
+    if let Some(at) = item.created {
+        props.push(format!("CREATED:{}", utc_stamp(at)));
+    }
+    if !item.tags.is_empty() {
+        let tags: Vec<String> = item.tags.iter().map(|t| escape(t)).collect();
+        props.push(format!("CATEGORIES:{}", tags.join(",")));
+    }
+    if let Some(ref p) = item.project {
+        props.push(format!("X-ZENITH-PROJECT:{}", escape(p)));
+    }
+
+    let mut out = String::new();
+    let Some(previous) = previous else {
+        for line in [
+            "BEGIN:VCALENDAR",
+            "VERSION:2.0",
+            "PRODID:-//Zenith//Todo//EN",
+        ] {
+            fold(line, &mut out);
+        }
+        fold("BEGIN:VTODO", &mut out);
+        props.iter().for_each(|p| fold(p, &mut out));
+        for line in ["END:VTODO", "END:VCALENDAR"] {
+            fold(line, &mut out);
+        }
+        return out;
+    };
+
+    // Replay the previous copy, swapping in fresh managed properties at the
+    // top of the VTODO and dropping the stale ones.
+    let mut depth = 0;
+    let mut in_todo = false;
+    for raw in unfold(previous) {
+        let line = parse_line(&raw);
+        let name = line.as_ref().map_or("", |l| l.name.as_str());
+        let value = line
+            .as_ref()
+            .map_or(String::new(), |l| l.value.to_ascii_uppercase());
+
+        if name == "BEGIN" && value == "VTODO" && !in_todo && depth == 0 {
+            in_todo = true;
+            depth = 1;
+            fold(&raw, &mut out);
+            props.iter().for_each(|p| fold(p, &mut out));
+            continue;
+        }
+        if in_todo {
+            match name {
+                "BEGIN" => depth += 1,
+                "END" => depth -= 1,
+                _ if depth == 1 && MANAGED.contains(&name) => continue,
+                _ => {}
+            }
+            if depth == 0 {
+                in_todo = false;
+            }
+        }
+        fold(&raw, &mut out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 18)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn reads_a_vtodo_from_another_client() {
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Other//EN\r\n\
+            BEGIN:VTODO\r\nUID:abc-123\r\nSUMMARY:Buy milk\\, eggs\r\n\
+            PRIORITY:3\r\nDUE;VALUE=DATE:20261020\r\nSTATUS:NEEDS-ACTION\r\n\
+            CATEGORIES:home,errands\r\nBEGIN:VALARM\r\nACTION:DISPLAY\r\n\
+            SUMMARY:Alarm text\r\nEND:VALARM\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+        let item = parse_vtodo(ics).unwrap();
+        assert_eq!(item.external_id.as_deref(), Some("abc-123"));
+        assert_eq!(item.text, "Buy milk, eggs");
+        assert_eq!(item.priority, 3);
+        assert_eq!(item.tags, vec!["home", "errands"]);
+        assert!(!item.done);
+        assert_eq!(
+            item.due,
+            Some(Due {
+                date: NaiveDate::from_ymd_opt(2026, 10, 20).unwrap(),
+                time: None
+            })
+        );
+    }
+
+    #[test]
+    fn written_vtodo_reads_back() {
+        let item = TodoItem {
+            text: "Deploy; then, celebrate".into(),
+            done: true,
+            priority: 2,
+            completed_at: Some(now()),
+            due: Some(Due {
+                date: NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+                time: chrono::NaiveTime::from_hms_opt(9, 30, 0),
+            }),
+            project: Some("ops".into()),
+            tags: vec!["work".into()],
+            external_id: Some("uid-1".into()),
+            ..Default::default()
+        };
+        let back = parse_vtodo(&write_vtodo(&item, None, now())).unwrap();
+        assert_eq!(back.text, item.text);
+        assert!(back.done);
+        assert_eq!(back.priority, 2);
+        assert_eq!(back.due, item.due);
+        assert_eq!(back.completed_at, item.completed_at);
+        assert_eq!(back.project, item.project);
+        assert_eq!(back.tags, item.tags);
+        assert_eq!(back.external_id, item.external_id);
+    }
+
+    #[test]
+    fn rewriting_keeps_unmanaged_properties() {
+        let previous = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Other//EN\r\n\
+            BEGIN:VTODO\r\nUID:abc\r\nSUMMARY:Old\r\nRRULE:FREQ=WEEKLY\r\n\
+            X-OTHER-CLIENT:keep me\r\nBEGIN:VALARM\r\nACTION:DISPLAY\r\n\
+            SUMMARY:Alarm\r\nEND:VALARM\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+        let mut item = parse_vtodo(previous).unwrap();
+        item.text = "New".into();
+
+        let ics = write_vtodo(&item, Some(previous), now());
+        assert!(ics.contains("SUMMARY:New\r\n"));
+        assert!(!ics.contains("SUMMARY:Old"));
+        assert!(ics.contains("RRULE:FREQ=WEEKLY\r\n"));
+        assert!(ics.contains("X-OTHER-CLIENT:keep me\r\n"));
+        assert!(ics.contains("SUMMARY:Alarm\r\n"));
+        assert!(ics.contains("PRODID:-//Other//EN\r\n"));
+        assert_eq!(ics.matches("UID:").count(), 1);
+    }
+
+    #[test]
+    fn long_lines_are_folded_and_unfolded() {
+        let item = TodoItem {
+            text: "ä".repeat(80),
+            external_id: Some("u".into()),
+            ..Default::default()
+        };
+        let ics = write_vtodo(&item, None, now());
+        assert!(ics.split("\r\n").all(|l| l.len() <= 75));
+        assert_eq!(parse_vtodo(&ics).unwrap().text, item.text);
+    }
+}
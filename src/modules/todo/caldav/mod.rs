@@ -0,0 +1,394 @@
+//! Two-way sync of the task list with a CalDAV collection.
+//!
+//! Network I/O runs on a worker thread; merging happens on the GTK thread so
+//! the store is never shared across threads. Local changes that have not
+//! reached the server are simply the difference between the list and the
+//! last synced state, so they survive restarts and go out once the server
+//! is reachable again.
+//!
+//! With several named lists only the first one is synced.
+
+mod client;
+mod ical;
+mod plan;
+
+use anyhow::{anyhow, Result};
+use chrono::{Local, NaiveDateTime};
+use gtk4::prelude::*;
+use gtk4::{gio, Button};
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use super::{backend, RefreshCallback, TodoItem, TodoStore};
+use crate::config::CaldavConfig;
+use client::{DavClient, Offline, Written};
+use plan::{Op, SyncState};
+
+/// Wait after a local change before pushing it, so a burst of edits goes
+/// out in one sync.
+const DEBOUNCE_SECS: u32 = 3;
+
+/// Index of the list kept in sync.
+const SYNCED_LIST: usize = 0;
+
+enum Status {
+    Idle,
+    Syncing,
+    Synced(NaiveDateTime),
+    Offline,
+    Failed(String),
+}
+
+/// Runs syncs and keeps the header indicator up to date.
+pub struct CaldavSync {
+    client: Arc<DavClient>,
+    store: Rc<RefCell<TodoStore>>,
+    refresh: RefreshCallback,
+    state: RefCell<SyncState>,
+    /// Where `state` is kept between runs.
+    state_path: PathBuf,
+    status: RefCell<Status>,
+    /// Conflicts resolved during the last sync.
+    conflicts: Cell<usize>,
+    running: Cell<bool>,
+    /// A sync was requested while one was running.
+    again: Cell<bool>,
+    debounce: RefCell<Option<glib::SourceId>>,
+    indicator: Button,
+}
+
+impl CaldavSync {
+    /// `None` when no URL is configured.
+    pub fn new(
+        cfg: &CaldavConfig,
+        store: Rc<RefCell<TodoStore>>,
+        refresh: RefreshCallback,
+    ) -> Option<Rc<Self>> {
+        if cfg.url.trim().is_empty() {
+            return None;
+        }
+        let password = cfg
+            .password_command
+            .as_deref()
+            .and_then(run_password_command)
+            .or_else(|| cfg.password.clone());
+
+        let indicator = Button::new();
+        indicator.add_css_class("zenith-todo-sync");
+        let state_path = state_path(cfg.url.trim());
+
+        let sync = Rc::new(Self {
+            client: Arc::new(DavClient::new(
+                cfg.url.trim(),
+                &cfg.username,
+                password.as_deref(),
+            )),
+            store,
+            refresh,
+            state: RefCell::new(load_state(&state_path)),
+            state_path,
+            status: RefCell::new(Status::Idle),
+            conflicts: Cell::new(0),
+            running: Cell::new(false),
+            again: Cell::new(false),
+            debounce: RefCell::new(None),
+            indicator,
+        });
+
+        sync.indicator.connect_clicked({
+            let this = Rc::downgrade(&sync);
+            move |_| {
+                if let Some(this) = this.upgrade() {
+                    this.sync_now();
+                }
+            }
+        });
+
+        let this = Rc::downgrade(&sync);
+        glib::timeout_add_seconds_local(cfg.interval_secs.max(30), move || match this.upgrade() {
+            Some(this) => {
+                this.sync_now();
+                glib::ControlFlow::Continue
+            }
+            None => glib::ControlFlow::Break,
+        });
+
+        sync.sync_now();
+        Some(sync)
+    }
+
+    /// Header button showing the sync state; click to sync now.
+    pub fn indicator(&self) -> &Button {
+        &self.indicator
+    }
+
+    /// Called after every refresh: update the indicator and schedule a push
+    /// if anything changed locally.
+    pub fn changed(self: &Rc<Self>) {
+        let pending = self.pending();
+        self.update_indicator(pending);
+
+        // While offline or failing, leave retries to the periodic timer.
+        let healthy = matches!(*self.status.borrow(), Status::Idle | Status::Synced(_));
+        if pending == 0 || self.running.get() || !healthy || self.debounce.borrow().is_some() {
+            return;
+        }
+        let this = Rc::downgrade(self);
+        let source = glib::timeout_add_seconds_local_once(DEBOUNCE_SECS, move || {
+            if let Some(this) = this.upgrade() {
+                this.debounce.borrow_mut().take();
+                this.sync_now();
+            }
+        });
+        *self.debounce.borrow_mut() = Some(source);
+    }
+
+    fn pending(&self) -> usize {
+        let synced: Vec<TodoItem> = self
+            .store
+            .borrow()
+            .items
+            .iter()
+            .filter(|t| t.list == SYNCED_LIST)
+            .cloned()
+            .collect();
+        self.state.borrow().pending(&synced)
+    }
+
+    /// Fetch, merge and push. A request during a running sync starts another
+    /// one right after it.
+    fn sync_now(self: &Rc<Self>) {
+        if self.running.replace(true) {
+            self.again.set(true);
+            return;
+        }
+        if let Some(source) = self.debounce.borrow_mut().take() {
+            source.remove();
+        }
+        *self.status.borrow_mut() = Status::Syncing;
+        self.update_indicator(self.pending());
+
+        let this = Rc::clone(self);
+        glib::spawn_future_local(async move {
+            let result = this.run().await;
+            this.finish(result);
+        });
+    }
+
+    async fn run(&self) -> Result<usize> {
+        let client = Arc::clone(&self.client);
+        let remote = gio::spawn_blocking(move || client.list())
+            .await
+            .map_err(|_| anyhow!("sync thread panicked"))??;
+
+        let (ops, mut conflicts) = self.merge(remote);
+        if ops.is_empty() {
+            return Ok(conflicts);
+        }
+
+        let client = Arc::clone(&self.client);
+        let (results, error) = gio::spawn_blocking(move || execute(&client, ops))
+            .await
+            .map_err(|_| anyhow!("sync thread panicked"))?;
+
+        conflicts += plan::apply(&mut self.state.borrow_mut(), results);
+        save_state(&self.state_path, &self.state.borrow());
+        match error {
+            Some(e) => Err(e),
+            None => Ok(conflicts),
+        }
+    }
+
+    /// Fold the server's tasks into the store and return the writes to send.
+    fn merge(&self, remote: Vec<client::Resource>) -> (Vec<Op>, usize) {
+        let now = Local::now().naive_local();
+        let mut store = self.store.borrow_mut();
+        let synced: Vec<TodoItem> = store
+            .items
+            .iter()
+            .filter(|t| t.list == SYNCED_LIST)
+            .cloned()
+            .collect();
+        let plan = plan::plan(
+            &synced,
+            remote,
+            &self.state.borrow(),
+            |uid| self.client.href_for(uid),
+            now,
+        );
+
+        // Tasks arriving from the server land in the synced list. The
+        // merge is not an edit of the user's, so rather than adding an undo
+        // step it is written into the existing ones: undo and redo keep
+        // walking through the user's own changes on top of it.
+        let items = plan::keep_order(store.items.clone(), SYNCED_LIST, plan.items);
+        if !same_items(&store.items, &items) {
+            let before = std::mem::replace(&mut store.items, items);
+            store.assign_ids();
+            let after = store.items.clone();
+            store.rebase_history(|snapshot| {
+                plan::replay(snapshot, SYNCED_LIST, &before, &after);
+            });
+            store.save();
+        }
+        drop(store);
+
+        *self.state.borrow_mut() = plan.state;
+        save_state(&self.state_path, &self.state.borrow());
+        (plan.ops, plan.conflicts)
+    }
+
+    fn finish(self: &Rc<Self>, result: Result<usize>) {
+        let status = match result {
+            Ok(conflicts) => {
+                self.conflicts.set(conflicts);
+                Status::Synced(Local::now().naive_local())
+            }
+            Err(e) if e.is::<Offline>() => {
+                log::info!("CalDAV sync postponed: {e:#}");
+                Status::Offline
+            }
+            Err(e) => {
+                log::warn!("CalDAV sync failed: {e:#}");
+                Status::Failed(format!("{e:#}"))
+            }
+        };
+        *self.status.borrow_mut() = status;
+        self.running.set(false);
+
+        if let Some(ref f) = *self.refresh.borrow() {
+            f();
+        }
+        if self.again.replace(false) {
+            self.sync_now();
+        }
+    }
+
+    fn update_indicator(&self, pending: usize) {
+        let btn = &self.indicator;
+        for class in [
+            "zenith-todo-sync-ok",
+            "zenith-todo-sync-busy",
+            "zenith-todo-sync-offline",
+            "zenith-todo-sync-error",
+        ] {
+            btn.remove_css_class(class);
+        }
+
+        let queued = match pending {
+            0 => String::new(),
+            1 => " · 1 change queued".to_string(),
+            n => format!(" · {n} changes queued"),
+        };
+        let (label, class, tooltip) = match *self.status.borrow() {
+            Status::Idle => (
+                "⟳",
+                "zenith-todo-sync-busy",
+                format!("Not synced yet{queued}"),
+            ),
+            Status::Syncing => ("⟳", "zenith-todo-sync-busy", "Syncing…".to_string()),
+            Status::Synced(at) => {
+                let mut tip = format!("Synced at {}{queued}", at.format("%H:%M"));
+                match self.conflicts.get() {
+                    0 => {}
+                    n => tip.push_str(&format!(
+                        "\n{n} conflict(s): the server's version was kept and \
+                         yours added as a \"(conflict)\" copy"
+                    )),
+                }
+                let label = if self.conflicts.get() > 0 {
+                    "⚠"
+                } else {
+                    "✓"
+                };
+                (label, "zenith-todo-sync-ok", tip)
+            }
+            Status::Offline => ("⌁", "zenith-todo-sync-offline", format!("Offline{queued}")),
+            Status::Failed(ref msg) => (
+                "✕",
+                "zenith-todo-sync-error",
+                format!("Sync failed: {msg}{queued}"),
+            ),
+        };
+        btn.set_label(label);
+        btn.add_css_class(class);
+        btn.set_tooltip_text(Some(&format!("{tooltip}\nClick to sync now")));
+    }
+}
+
+/// Send `ops` in order, stopping at the first error. Conflicts are not
+/// errors; they are reported per op.
+fn execute(client: &DavClient, ops: Vec<Op>) -> (Vec<(Op, Written)>, Option<anyhow::Error>) {
+    let mut results = Vec::with_capacity(ops.len());
+    for op in ops {
+        let written = match op {
+            Op::Put {
+                ref href,
+                ref ics,
+                ref etag,
+                ..
+            } => client.put(href, ics, etag.as_deref()),
+            Op::Delete {
+                ref href, ref etag, ..
+            } => client.delete(href, etag.as_deref()),
+        };
+        match written {
+            Ok(w) => results.push((op, w)),
+            Err(e) => return (results, Some(e)),
+        }
+    }
+    (results, None)
+}
+
+fn run_password_command(cmd: &str) -> Option<String> {
+    let output = Command::new("sh").arg("-c").arg(cmd).output();
+    match output {
+        Ok(out) if out.status.success() => String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .next()
+            .map(str::to_string),
+        Ok(out) => {
+            log::warn!("CalDAV password command exited with {}", out.status);
+            None
+        }
+        Err(e) => {
+            log::warn!("Failed to run CalDAV password command: {e}");
+            None
+        }
+    }
+}
+
+/// Whether two lists hold the same tasks in the same order.
+fn same_items(a: &[TodoItem], b: &[TodoItem]) -> bool {
+    a.len() == b.len() && serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// Path: $XDG_DATA_HOME/zenith/caldav-state-<hash of url>.json, so pointing
+/// the bar at another collection never mixes up two sync histories.
+fn state_path(url: &str) -> PathBuf {
+    // FNV-1a: stable across builds, unlike `DefaultHasher`.
+    let hash = url.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+    backend::data_file(&format!("caldav-state-{hash:016x}.json"))
+}
+
+fn load_state(path: &Path) -> SyncState {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(path: &Path, state: &SyncState) {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string(state) {
+        let _ = fs::write(path, json);
+    }
+}
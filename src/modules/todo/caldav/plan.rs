@@ -0,0 +1,617 @@
+//! The pure part of a sync: deciding what changed where.
+//!
+//! [`SyncState`] remembers every task as it looked after the last successful
+//! sync. A task differing from that snapshot changed locally; a resource
+//! whose ETag differs changed on the server. Anything not yet pushed is
+//! simply a difference against the snapshot, which is what makes edits
+//! made while offline go out on the next successful sync.
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::client::{Resource, Written};
+use super::ical;
+use crate::modules::todo::TodoItem;
+
+/// Per-UID record of the last synced version.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncState {
+    pub entries: HashMap<String, Entry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub href: String,
+    pub etag: Option<String>,
+    /// The server's copy, so unmanaged properties survive our writes.
+    pub ics: String,
+    pub synced: TodoItem,
+}
+
+impl SyncState {
+    /// How many local changes have not reached the server yet.
+    pub fn pending(&self, items: &[TodoItem]) -> usize {
+        let mut live = HashSet::new();
+        let changed = items
+            .iter()
+            .filter(|t| {
+                let entry = t.external_id.as_ref().and_then(|uid| {
+                    live.insert(uid.as_str());
+                    self.entries.get(uid)
+                });
+                entry.is_none_or(|e| !same_content(&e.synced, t))
+            })
+            .count();
+        let deleted = self
+            .entries
+            .keys()
+            .filter(|uid| !live.contains(uid.as_str()))
+            .count();
+        changed + deleted
+    }
+}
+
+/// A write to send to the server.
+#[derive(Debug, Clone)]
+pub enum Op {
+    Put {
+        uid: String,
+        href: String,
+        ics: String,
+        etag: Option<String>,
+        item: Box<TodoItem>,
+    },
+    Delete {
+        uid: String,
+        href: String,
+        etag: Option<String>,
+    },
+}
+
+/// Outcome of [`plan`]: the merged list to show right away, the state for
+/// everything already in agreement, and the writes still to make.
+#[derive(Debug)]
+pub struct Plan {
+    pub items: Vec<TodoItem>,
+    pub state: SyncState,
+    pub ops: Vec<Op>,
+    pub conflicts: usize,
+}
+
+/// Whether two versions agree on every field the server stores.
+pub fn same_content(a: &TodoItem, b: &TodoItem) -> bool {
+    a.text == b.text
+        && a.done == b.done
+        && a.priority == b.priority
+        && a.due == b.due
+        && a.project == b.project
+        && a.tags == b.tags
+}
+
+/// A fresh UID for a task the server has not seen.
+pub fn new_uid(now: NaiveDateTime) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    format!(
+        "zenith-{}-{}-{}",
+        now.and_utc().timestamp_micros(),
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Copy the server-managed fields of `remote` onto `local`, keeping Zenith's
+/// id, subtasks and other local-only data.
+fn take_remote(local: &TodoItem, remote: &TodoItem) -> TodoItem {
+    TodoItem {
+        text: remote.text.clone(),
+        done: remote.done,
+        priority: remote.priority,
+        due: remote.due,
+        project: remote.project.clone(),
+        tags: remote.tags.clone(),
+        completed_at: remote
+            .completed_at
+            .or(local.completed_at.filter(|_| remote.done)),
+        created: local.created.or(remote.created),
+        external_id: remote.external_id.clone(),
+        ..local.clone()
+    }
+}
+
+/// Merge `local` with the server's `remote` resources.
+///
+/// - changed on one side only: that side wins
+/// - changed on both: the server wins and the local version is kept as a
+///   new task marked "(conflict)"
+/// - deleted on the server, unchanged here: removed locally
+/// - deleted here, unchanged on the server: deleted on the server
+pub fn plan(
+    local: &[TodoItem],
+    remote: Vec<Resource>,
+    state: &SyncState,
+    href_for: impl Fn(&str) -> String,
+    now: NaiveDateTime,
+) -> Plan {
+    let mut remote: HashMap<String, (Resource, TodoItem)> = remote
+        .into_iter()
+        .filter_map(|res| {
+            let item = ical::parse_vtodo(&res.ics)?;
+            Some((item.external_id.clone()?, (res, item)))
+        })
+        .collect();
+
+    let mut out = Plan {
+        items: Vec::new(),
+        state: SyncState::default(),
+        ops: Vec::new(),
+        conflicts: 0,
+    };
+
+    let put_new = |item: TodoItem, out: &mut Plan| {
+        let mut item = item;
+        let uid = item.external_id.get_or_insert_with(|| new_uid(now)).clone();
+        out.ops.push(Op::Put {
+            href: href_for(&uid),
+            ics: ical::write_vtodo(&item, None, now),
+            etag: None,
+            uid,
+            item: Box::new(item.clone()),
+        });
+        out.items.push(item);
+    };
+
+    for item in local {
+        let Some(uid) = item.external_id.clone() else {
+            put_new(item.clone(), &mut out);
+            continue;
+        };
+        let entry = state.entries.get(&uid);
+        let local_changed = entry.is_none_or(|e| !same_content(&e.synced, item));
+
+        match (remote.remove(&uid), entry) {
+            (Some((res, theirs)), _) => {
+                let remote_changed = entry.is_none_or(|e| e.etag != res.etag);
+                // Unchanged here, or changed to exactly what the server has.
+                let agreed = !local_changed || same_content(item, &theirs);
+
+                if agreed {
+                    let merged = take_remote(item, &theirs);
+                    out.state.entries.insert(
+                        uid,
+                        Entry {
+                            href: res.href,
+                            etag: res.etag,
+                            ics: res.ics,
+                            synced: merged.clone(),
+                        },
+                    );
+                    out.items.push(merged);
+                } else if !remote_changed {
+                    out.ops.push(Op::Put {
+                        uid: uid.clone(),
+                        href: res.href.clone(),
+                        ics: ical::write_vtodo(item, Some(&res.ics), now),
+                        etag: res.etag.clone(),
+                        item: Box::new(item.clone()),
+                    });
+                    if let Some(e) = entry {
+                        out.state.entries.insert(uid, e.clone());
+                    }
+                    out.items.push(item.clone());
+                } else {
+                    out.conflicts += 1;
+                    let merged = take_remote(item, &theirs);
+                    out.state.entries.insert(
+                        uid,
+                        Entry {
+                            href: res.href,
+                            etag: res.etag,
+                            ics: res.ics,
+                            synced: merged.clone(),
+                        },
+                    );
+                    out.items.push(merged);
+
+                    let copy = TodoItem {
+                        id: 0,
+                        text: format!("{} (conflict)", item.text),
+                        external_id: None,
+                        ..item.clone()
+                    };
+                    put_new(copy, &mut out);
+                }
+            }
+            // Deleted on the server. Keep it only if it was edited here.
+            (None, Some(_)) if !local_changed => {}
+            (None, Some(_)) => {
+                let fresh = TodoItem {
+                    external_id: None,
+                    ..item.clone()
+                };
+                put_new(fresh, &mut out);
+            }
+            // Has a UID but was never synced (e.g. an earlier push failed).
+            (None, None) => put_new(item.clone(), &mut out),
+        }
+    }
+
+    // Resources with no local counterpart: new on the server, or deleted here.
+    for (uid, (res, theirs)) in remote {
+        match state.entries.get(&uid) {
+            Some(e) if e.etag == res.etag => out.ops.push(Op::Delete {
+                uid,
+                href: res.href,
+                etag: res.etag,
+            }),
+            // New, or edited on the server after we deleted it: bring it in.
+            _ => {
+                out.state.entries.insert(
+                    uid,
+                    Entry {
+                        href: res.href,
+                        etag: res.etag,
+                        ics: res.ics,
+                        synced: theirs.clone(),
+                    },
+                );
+                out.items.push(TodoItem { id: 0, ..theirs });
+            }
+        }
+    }
+
+    out
+}
+
+/// Put the merged tasks of `list` back where that list's tasks sat in
+/// `items`, leaving every other list alone. A task new to the list follows
+/// the one planned just before it (so a conflict copy sits under its
+/// original); any before the first known task go at the end.
+pub fn keep_order(items: Vec<TodoItem>, list: usize, merged: Vec<TodoItem>) -> Vec<TodoItem> {
+    let known: HashSet<u64> = items
+        .iter()
+        .filter(|t| t.list == list)
+        .map(|t| t.id)
+        .collect();
+    let mut after: HashMap<u64, Vec<TodoItem>> = HashMap::new();
+    let mut loose = Vec::new();
+    let mut anchor = None;
+    for item in merged {
+        if item.id != 0 && known.contains(&item.id) {
+            anchor = Some(item.id);
+        }
+        match anchor {
+            Some(id) => after.entry(id).or_default().push(item),
+            None => loose.push(item),
+        }
+    }
+
+    let mut out = Vec::with_capacity(items.len() + loose.len());
+    for item in items {
+        if item.list != list {
+            out.push(item);
+        } else if let Some(group) = after.remove(&item.id) {
+            out.extend(group);
+        }
+    }
+    out.extend(loose);
+    out
+}
+
+/// Carry what a merge did to `list` (going from `before` to `after`, both
+/// with ids assigned) into an older `snapshot` of the store: tasks the merge
+/// changed are swapped for the new version, removed ones dropped and new
+/// ones appended. Tasks the merge left alone keep their older state.
+pub fn replay(snapshot: &mut Vec<TodoItem>, list: usize, before: &[TodoItem], after: &[TodoItem]) {
+    let as_json = |t: &TodoItem| serde_json::to_value(t).ok();
+    let old: HashMap<u64, _> = before
+        .iter()
+        .filter(|t| t.list == list)
+        .map(|t| (t.id, as_json(t)))
+        .collect();
+    let new: HashMap<u64, &TodoItem> = after
+        .iter()
+        .filter(|t| t.list == list)
+        .map(|t| (t.id, t))
+        .collect();
+
+    snapshot.retain(|t| t.list != list || !old.contains_key(&t.id) || new.contains_key(&t.id));
+    for item in snapshot.iter_mut().filter(|t| t.list == list) {
+        match (old.get(&item.id), new.get(&item.id)) {
+            (Some(was), Some(now)) if *was != as_json(now) => *item = (*now).clone(),
+            _ => {}
+        }
+    }
+    let present: HashSet<u64> = snapshot.iter().map(|t| t.id).collect();
+    snapshot.extend(
+        after
+            .iter()
+            .filter(|t| t.list == list && !old.contains_key(&t.id) && !present.contains(&t.id))
+            .cloned(),
+    );
+}
+
+/// Fold the results of the writes back into `state`. Failed or conflicting
+/// writes leave the previous entry in place so the next sync retries them.
+/// Returns the number of conflicts.
+pub fn apply(state: &mut SyncState, results: Vec<(Op, Written)>) -> usize {
+    let mut conflicts = 0;
+    for (op, written) in results {
+        match (op, written) {
+            (_, Written::Conflict) => conflicts += 1,
+            (
+                Op::Put {
+                    uid,
+                    href,
+                    ics,
+                    item,
+                    ..
+                },
+                Written::Ok(etag),
+            ) => {
+                state.entries.insert(
+                    uid,
+                    Entry {
+                        href,
+                        etag,
+                        ics,
+                        synced: *item,
+                    },
+                );
+            }
+            (Op::Delete { uid, .. }, Written::Ok(_)) => {
+                state.entries.remove(&uid);
+            }
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 18)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+    }
+
+    fn task(uid: &str, text: &str) -> TodoItem {
+        TodoItem {
+            id: 1,
+            text: text.into(),
+            external_id: Some(uid.into()),
+            ..Default::default()
+        }
+    }
+
+    fn resource(item: &TodoItem, etag: &str) -> Resource {
+        let uid = item.external_id.as_deref().unwrap();
+        Resource {
+            href: href(uid),
+            etag: Some(etag.into()),
+            ics: ical::write_vtodo(item, None, now()),
+        }
+    }
+
+    fn href(uid: &str) -> String {
+        format!("http://dav/tasks/{}.ics", uid)
+    }
+
+    /// State as if `items` were last synced with the given ETag.
+    fn synced(items: &[(&TodoItem, &str)]) -> SyncState {
+        let mut state = SyncState::default();
+        for (item, etag) in items {
+            let res = resource(item, etag);
+            let uid = item.external_id.clone().unwrap();
+            state.entries.insert(
+                uid,
+                Entry {
+                    href: res.href,
+                    etag: res.etag,
+                    ics: res.ics,
+                    synced: (*item).clone(),
+                },
+            );
+        }
+        state
+    }
+
+    #[test]
+    fn new_local_task_is_created_on_the_server() {
+        let local = vec![TodoItem {
+            text: "New".into(),
+            ..Default::default()
+        }];
+        let p = plan(&local, vec![], &SyncState::default(), href, now());
+        assert_eq!(p.ops.len(), 1);
+        assert!(matches!(&p.ops[0], Op::Put { etag: None, .. }));
+        assert!(p.items[0].external_id.is_some());
+    }
+
+    #[test]
+    fn local_edit_is_pushed_with_the_known_etag() {
+        let base = task("a", "Old");
+        let state = synced(&[(&base, "e1")]);
+        let edited = task("a", "New");
+        let p = plan(&[edited], vec![resource(&base, "e1")], &state, href, now());
+        match &p.ops[..] {
+            [Op::Put { etag, item, .. }] => {
+                assert_eq!(etag.as_deref(), Some("e1"));
+                assert_eq!(item.text, "New");
+            }
+            other => panic!("unexpected ops {other:?}"),
+        }
+        assert_eq!(p.state.pending(&p.items), 1);
+    }
+
+    #[test]
+    fn remote_edit_is_pulled_keeping_local_fields() {
+        let base = task("a", "Old");
+        let state = synced(&[(&base, "e1")]);
+        let mut local = base.clone();
+        local.id = 7;
+        local.subtasks.push(crate::modules::todo::Subtask {
+            text: "sub".into(),
+            done: false,
+        });
+        let theirs = task("a", "Remote");
+        let p = plan(&[local], vec![resource(&theirs, "e2")], &state, href, now());
+        assert!(p.ops.is_empty());
+        assert_eq!(p.items[0].text, "Remote");
+        assert_eq!(p.items[0].id, 7);
+        assert_eq!(p.items[0].subtasks.len(), 1);
+        assert_eq!(p.state.pending(&p.items), 0);
+    }
+
+    #[test]
+    fn edits_on_both_sides_keep_both_versions() {
+        let base = task("a", "Old");
+        let state = synced(&[(&base, "e1")]);
+        let p = plan(
+            &[task("a", "Mine")],
+            vec![resource(&task("a", "Theirs"), "e2")],
+            &state,
+            href,
+            now(),
+        );
+        assert_eq!(p.conflicts, 1);
+        let texts: Vec<&str> = p.items.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["Theirs", "Mine (conflict)"]);
+        assert!(matches!(&p.ops[..], [Op::Put { etag: None, .. }]));
+    }
+
+    #[test]
+    fn deletions_propagate_both_ways() {
+        let gone_remote = task("a", "A");
+        let gone_local = task("b", "B");
+        let state = synced(&[(&gone_remote, "e1"), (&gone_local, "e1")]);
+        let p = plan(
+            std::slice::from_ref(&gone_remote),
+            vec![resource(&gone_local, "e1")],
+            &state,
+            href,
+            now(),
+        );
+        assert!(p.items.is_empty());
+        assert!(matches!(&p.ops[..], [Op::Delete { uid, .. }] if uid == "b"));
+    }
+
+    #[test]
+    fn new_remote_task_is_added_locally() {
+        let theirs = task("r", "From phone");
+        let p = plan(
+            &[],
+            vec![resource(&theirs, "e1")],
+            &SyncState::default(),
+            href,
+            now(),
+        );
+        assert_eq!(p.items.len(), 1);
+        assert_eq!(p.items[0].id, 0);
+        assert!(p.ops.is_empty());
+    }
+
+    #[test]
+    fn failed_and_conflicting_writes_stay_pending() {
+        let base = task("a", "Old");
+        let mut state = synced(&[(&base, "e1")]);
+        let edited = task("a", "New");
+        let p = plan(
+            std::slice::from_ref(&edited),
+            vec![resource(&base, "e1")],
+            &state,
+            href,
+            now(),
+        );
+
+        // Offline: no results at all, the edit is still pending.
+        assert_eq!(state.pending(std::slice::from_ref(&edited)), 1);
+
+        let conflicts = apply(&mut state, vec![(p.ops[0].clone(), Written::Conflict)]);
+        assert_eq!(conflicts, 1);
+        assert_eq!(state.pending(std::slice::from_ref(&edited)), 1);
+
+        apply(
+            &mut state,
+            vec![(p.ops[0].clone(), Written::Ok(Some("e2".into())))],
+        );
+        assert_eq!(state.pending(&[edited]), 0);
+        assert_eq!(state.entries["a"].etag.as_deref(), Some("e2"));
+    }
+
+    #[test]
+    fn merge_keeps_the_local_order() {
+        let item = |id, list, text: &str| TodoItem {
+            id,
+            list,
+            text: text.into(),
+            ..Default::default()
+        };
+        let items = vec![
+            item(1, 0, "a"),
+            item(2, 1, "other list"),
+            item(3, 0, "b"),
+            item(4, 0, "deleted remotely"),
+            item(5, 0, "c"),
+        ];
+        let merged = vec![
+            item(1, 0, "a"),
+            item(0, 0, "a (conflict)"),
+            item(3, 0, "b"),
+            item(5, 0, "c"),
+            item(0, 0, "from server"),
+        ];
+        let texts: Vec<String> = keep_order(items, 0, merged)
+            .into_iter()
+            .map(|t| t.text)
+            .collect();
+        assert_eq!(
+            texts,
+            ["a", "a (conflict)", "other list", "b", "c", "from server"]
+        );
+    }
+
+    #[test]
+    fn replay_carries_only_the_merge_into_a_snapshot() {
+        let item = |id, list, text: &str| TodoItem {
+            id,
+            list,
+            text: text.into(),
+            ..Default::default()
+        };
+        let before = vec![
+            item(1, 0, "edited here"),
+            item(2, 0, "b"),
+            item(3, 0, "c"),
+            item(4, 1, "other list"),
+        ];
+        let after = vec![
+            item(1, 0, "edited here"),
+            item(2, 0, "b from server"),
+            item(5, 0, "new on server"),
+            item(4, 1, "other list"),
+        ];
+        let mut snapshot = vec![
+            item(1, 0, "before the edit"),
+            item(2, 0, "b"),
+            item(3, 0, "c"),
+            item(6, 0, "deleted here since"),
+        ];
+        replay(&mut snapshot, 0, &before, &after);
+        let texts: Vec<&str> = snapshot.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            [
+                "before the edit",
+                "b from server",
+                "deleted here since",
+                "new on server"
+            ]
+        );
+    }
+}
@@ -0,0 +1,285 @@
+//! Importing and exporting tasks as GitHub-style markdown checklists or
+//! plain JSON.
+//!
+//! A markdown task is one checklist line; indented checklist lines below it
+//! become its subtasks:
+//!
+//! ```text
+//! - [ ] Deploy server +ops #infra prio:2 due:2026-10-20T14:00
+//!   - [x] Tag the release
+//! ```
+//!
+//! Priority and due date travel as `prio:` and `due:` tokens. On import the
+//! rest of the line goes through the add-task entry syntax, so `+project`,
+//! `#tag`, `3:` prefixes and phrases like "by friday" work in pasted notes.
+//! Other lines (headings, prose) are ignored.
+
+use anyhow::{bail, Context, Result};
+use chrono::{NaiveDate, NaiveDateTime};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::{parse, Due, Subtask, TodoItem};
+
+const DATE: &str = "%Y-%m-%d";
+const DATE_TIME: &str = "%Y-%m-%dT%H:%M";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Markdown,
+    Json,
+}
+
+impl Format {
+    /// Guess from a file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+
+    /// Guess from the contents: JSON starts with an array.
+    pub fn sniff(contents: &str) -> Self {
+        if contents.trim_start().starts_with('[') {
+            Format::Json
+        } else {
+            Format::Markdown
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Markdown => "md",
+            Format::Json => "json",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "md" | "markdown" => Ok(Format::Markdown),
+            "json" => Ok(Format::Json),
+            _ => bail!("Unknown format {s:?} (expected md or json)"),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Format::Markdown => "markdown",
+            Format::Json => "json",
+        })
+    }
+}
+
+/// Render `items` in `format`.
+pub fn export(items: &[TodoItem], format: Format) -> Result<String> {
+    match format {
+        Format::Markdown => Ok(to_markdown(items)),
+        Format::Json => Ok(serde_json::to_string_pretty(items)? + "\n"),
+    }
+}
+
+/// Read tasks from `contents`. The returned items have no ids yet; relative
+/// dates in markdown resolve against `now`.
+pub fn import(contents: &str, format: Format, now: NaiveDateTime) -> Result<Vec<TodoItem>> {
+    let mut items = match format {
+        Format::Markdown => from_markdown(contents, now),
+        Format::Json => serde_json::from_str(contents).context("Not a JSON list of tasks")?,
+    };
+    for item in &mut items {
+        // Imported copies are new tasks of this list.
+        item.id = 0;
+        item.external_id = None;
+        item.focus = false;
+        item.created.get_or_insert(now);
+    }
+    if items.is_empty() {
+        bail!("No tasks found (expected \"- [ ] task\" lines or a JSON list)");
+    }
+    Ok(items)
+}
+
+/// File name for an export made on `date`.
+pub fn export_name(date: NaiveDate, format: Format) -> String {
+    format!("zenith-tasks-{}.{}", date.format(DATE), format.extension())
+}
+
+pub fn to_markdown(items: &[TodoItem]) -> String {
+    let mut out = String::new();
+    for item in items {
+        out.push_str(&format!("- [{}] {}\n", mark(item.done), format_line(item)));
+        for sub in &item.subtasks {
+            out.push_str(&format!("  - [{}] {}\n", mark(sub.done), sub.text));
+        }
+    }
+    out
+}
+
+fn mark(done: bool) -> char {
+    if done {
+        'x'
+    } else {
+        ' '
+    }
+}
+
+/// Text, project and tags as entry syntax, then the metadata tokens.
+fn format_line(item: &TodoItem) -> String {
+    let plain = TodoItem {
+        text: item.text.clone(),
+        project: item.project.clone(),
+        tags: item.tags.clone(),
+        recurrence: item.recurrence,
+        remind: item.remind,
+        ..Default::default()
+    };
+    let mut line = parse::format_task(&plain);
+    if (1..=9).contains(&item.priority) {
+        line.push_str(&format!(" prio:{}", item.priority));
+    }
+    if let Some(due) = item.due {
+        let due = match due.time {
+            Some(_) => due.deadline().format(DATE_TIME).to_string(),
+            None => due.date.format(DATE).to_string(),
+        };
+        line.push_str(&format!(" due:{}", due));
+    }
+    line
+}
+
+pub fn from_markdown(contents: &str, now: NaiveDateTime) -> Vec<TodoItem> {
+    let mut items: Vec<TodoItem> = Vec::new();
+    for line in contents.lines() {
+        let indented = line.starts_with([' ', '\t']);
+        let Some((done, text)) = checklist_entry(line.trim_start()) else {
+            continue;
+        };
+        match items.last_mut() {
+            Some(parent) if indented => parent.subtasks.push(Subtask {
+                text: text.to_string(),
+                done,
+            }),
+            _ => items.push(parse_line(done, text, now)),
+        }
+    }
+    items
+}
+
+/// `- [ ] text`, `* [x] text` or `+ [X] text`.
+fn checklist_entry(line: &str) -> Option<(bool, &str)> {
+    let rest = line.strip_prefix(['-', '*', '+'])?.trim_start();
+    let rest = rest.strip_prefix('[')?;
+    let (done, rest) = match rest.split_once(']')? {
+        (" " | "", rest) => (false, rest),
+        ("x" | "X", rest) => (true, rest),
+        _ => return None,
+    };
+    let text = rest.trim();
+    (!text.is_empty()).then_some((done, text))
+}
+
+fn parse_line(done: bool, text: &str, now: NaiveDateTime) -> TodoItem {
+    let mut priority = None;
+    let mut due = None;
+    let rest: Vec<&str> = text
+        .split_whitespace()
+        .filter(|token| {
+            if let Some(p) = token.strip_prefix("prio:").and_then(|p| p.parse().ok()) {
+                priority = Some(p);
+            } else if let Some(d) = token.strip_prefix("due:").and_then(parse_due) {
+                due = Some(d);
+            } else {
+                return true;
+            }
+            false
+        })
+        .collect();
+
+    let mut item = TodoItem::default();
+    item.apply(parse::parse_task(&rest.join(" "), now));
+    if let Some(p) = priority.filter(|p| *p <= 9) {
+        item.priority = p;
+    }
+    if due.is_some() {
+        item.due = due;
+    }
+    if done {
+        item.done = true;
+        item.completed_at = Some(now);
+    }
+    item
+}
+
+fn parse_due(value: &str) -> Option<Due> {
+    if let Ok(at) = NaiveDateTime::parse_from_str(value, DATE_TIME) {
+        return Some(Due {
+            date: at.date(),
+            time: Some(at.time()),
+        });
+    }
+    NaiveDate::parse_from_str(value, DATE)
+        .ok()
+        .map(|date| Due { date, time: None })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 18)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn markdown_round_trip() {
+        let md = "\
+- [ ] Deploy server +ops #infra prio:2 due:2026-10-20T14:00
+  - [x] Tag the release
+- [x] Write notes due:2026-10-19
+";
+        let items = import(md, Format::Markdown, now()).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].text, "Deploy server");
+        assert_eq!(items[0].priority, 2);
+        assert_eq!(items[0].project.as_deref(), Some("ops"));
+        assert_eq!(items[0].subtasks.len(), 1);
+        assert!(items[1].done);
+        assert_eq!(to_markdown(&items), md);
+    }
+
+    #[test]
+    fn pasted_notes_use_entry_syntax() {
+        let md = "## Action items\nSome prose.\n* [ ] 3:Send slides tomorrow\n- plain bullet\n";
+        let items = import(md, Format::Markdown, now()).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].text, "Send slides");
+        assert_eq!(items[0].priority, 3);
+        assert_eq!(
+            items[0].due.map(|d| d.date),
+            NaiveDate::from_ymd_opt(2026, 10, 19)
+        );
+        assert!(import("# nothing here", Format::Markdown, now()).is_err());
+    }
+
+    #[test]
+    fn json_import_resets_identity() {
+        let items = vec![TodoItem {
+            id: 7,
+            text: "Review PR".into(),
+            priority: 1,
+            focus: true,
+            ..Default::default()
+        }];
+        let json = export(&items, Format::Json).unwrap();
+        assert_eq!(Format::sniff(&json), Format::Json);
+        let back = import(&json, Format::Json, now()).unwrap();
+        assert_eq!((back[0].id, back[0].priority, back[0].focus), (0, 1, false));
+    }
+}
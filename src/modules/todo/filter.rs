@@ -0,0 +1,163 @@
+use std::fmt;
+
+use super::fuzzy::{fuzzy_match, Match};
+use super::TodoItem;
+
+/// Labels for the priority-band dropdown, in `PriorityBand::from_index` order.
+pub const BAND_LABELS: [&str; 5] = ["Any priority", "High", "Mid", "Low", "No priority"];
+
+/// Labels for the status dropdown, in `Status::from_index` order.
+pub const STATUS_LABELS: [&str; 3] = ["All", "Pending", "Done"];
+
+/// What the popover list is currently narrowed down to.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TodoFilter {
+    pub scope: Scope,
+    pub band: Option<PriorityBand>,
+    pub status: Status,
+    /// Text typed into the search field.
+    pub query: String,
+}
+
+impl TodoFilter {
+    /// Whether anything is hidden: a search query, scope, band or status.
+    pub fn is_narrowed(&self) -> bool {
+        !self.query.trim().is_empty()
+            || self.scope != Scope::All
+            || self.band.is_some()
+            || self.status != Status::All
+    }
+
+    /// The search hit for `item` if it passes every part of the filter.
+    pub fn matches(&self, item: &TodoItem) -> Option<Match> {
+        let scope_ok = match &self.scope {
+            Scope::All => true,
+            Scope::Project(p) => item.project.as_deref() == Some(p.as_str()),
+            Scope::Tag(t) => item.tags.iter().any(|tag| tag == t),
+        };
+        let band_ok = self
+            .band
+            .is_none_or(|b| b == PriorityBand::of(item.priority));
+        let status_ok = match self.status {
+            Status::All => true,
+            Status::Pending => !item.done,
+            Status::Done => item.done,
+        };
+        if scope_ok && band_ok && status_ok {
+            self.search(item)
+        } else {
+            None
+        }
+    }
+
+    /// Fuzzy-match the search query against the text, project and tags,
+    /// keeping the best score. Positions only cover the text, since that is
+    /// all the row shows. An empty query matches everything.
+    pub fn search(&self, item: &TodoItem) -> Option<Match> {
+        let text = fuzzy_match(&self.query, &item.text);
+        let meta = item
+            .project
+            .iter()
+            .chain(&item.tags)
+            .filter_map(|s| fuzzy_match(&self.query, s))
+            .map(|m| Match {
+                // Prefer hits in the text itself over ones in the metadata.
+                score: m.score - 2,
+                positions: Vec::new(),
+            })
+            .max_by_key(|m| m.score);
+        match (text, meta) {
+            (Some(t), Some(m)) if m.score > t.score => Some(m),
+            (Some(t), _) => Some(t),
+            (None, m) => m,
+        }
+    }
+}
+
+/// Restrict the list to one project or tag.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Scope {
+    #[default]
+    All,
+    Project(String),
+    Tag(String),
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scope::All => write!(f, "All tags"),
+            Scope::Project(p) => write!(f, "+{}", p),
+            Scope::Tag(t) => write!(f, "#{}", t),
+        }
+    }
+}
+
+/// Every project and tag in use, projects first, each group sorted.
+pub fn scope_options<'a>(items: impl IntoIterator<Item = &'a TodoItem>) -> Vec<Scope> {
+    let mut scopes: Vec<Scope> = items
+        .into_iter()
+        .flat_map(|t| {
+            t.project
+                .iter()
+                .map(|p| Scope::Project(p.clone()))
+                .chain(t.tags.iter().map(|tag| Scope::Tag(tag.clone())))
+        })
+        .collect();
+    scopes.sort();
+    scopes.dedup();
+
+    let mut options = vec![Scope::All];
+    options.extend(scopes);
+    options
+}
+
+/// Priority grouping matching the accent colours in the list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityBand {
+    High,
+    Mid,
+    Low,
+    None,
+}
+
+impl PriorityBand {
+    pub fn of(priority: u8) -> Self {
+        match priority {
+            1..=3 => PriorityBand::High,
+            4..=6 => PriorityBand::Mid,
+            7..=9 => PriorityBand::Low,
+            _ => PriorityBand::None,
+        }
+    }
+
+    /// Map a `BAND_LABELS` index; 0 ("Any priority") yields `None`.
+    pub fn from_index(idx: u32) -> Option<Self> {
+        match idx {
+            1 => Some(PriorityBand::High),
+            2 => Some(PriorityBand::Mid),
+            3 => Some(PriorityBand::Low),
+            4 => Some(PriorityBand::None),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Status {
+    #[default]
+    All,
+    Pending,
+    Done,
+}
+
+impl Status {
+    /// Map a `STATUS_LABELS` index.
+    pub fn from_index(idx: u32) -> Self {
+        match idx {
+            1 => Status::Pending,
+            2 => Status::Done,
+            _ => Status::All,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+/// A successful fuzzy match.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Match {
+    /// Higher is better.
+    pub score: i32,
+    /// Char indices of the matched characters in the text.
+    pub positions: Vec<usize>,
+}
+
+/// Match `query` against `text` as a case-insensitive subsequence, ignoring
+/// whitespace in the query. Runs of consecutive characters and matches at
+/// word starts score higher; gaps cost a little.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<Match> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(lower)
+        .collect();
+    if query.is_empty() {
+        return Some(Match::default());
+    }
+    let text: Vec<char> = text.chars().collect();
+    let folded: Vec<char> = text.iter().copied().map(lower).collect();
+
+    // Try every occurrence of the first query character as the anchor and
+    // keep the best greedy match from there.
+    (0..folded.len())
+        .filter(|&start| folded[start] == query[0])
+        .filter_map(|start| match_from(&query, &text, &folded, start))
+        .max_by_key(|m| m.score)
+}
+
+fn match_from(query: &[char], text: &[char], folded: &[char], start: usize) -> Option<Match> {
+    let mut positions = Vec::with_capacity(query.len());
+    let mut at = start;
+    for &q in query {
+        let found = (at..folded.len()).find(|&i| folded[i] == q)?;
+        positions.push(found);
+        at = found + 1;
+    }
+
+    let mut score = -(start.min(5) as i32);
+    let mut prev: Option<usize> = None;
+    for &pos in &positions {
+        score += 1;
+        if pos == 0 || !text[pos - 1].is_alphanumeric() {
+            score += 4;
+        }
+        match prev {
+            Some(p) if pos == p + 1 => score += 5,
+            Some(p) => score -= ((pos - p - 1) as i32).min(3),
+            None => {}
+        }
+        prev = Some(pos);
+    }
+    Some(Match { score, positions })
+}
+
+fn lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_subsequence_ignoring_case_and_spaces() {
+        let m = fuzzy_match("dep srv", "Deploy server").unwrap();
+        assert_eq!(m.positions, vec![0, 1, 2, 7, 9, 10]);
+        assert!(fuzzy_match("xyz", "Deploy server").is_none());
+        assert_eq!(fuzzy_match("  ", "anything"), Some(Match::default()));
+    }
+
+    #[test]
+    fn prefers_word_starts_and_runs() {
+        // "se" should land on "server", not the "s" in "tests".
+        let m = fuzzy_match("se", "run tests on server").unwrap();
+        assert_eq!(m.positions, vec![13, 14]);
+        let tight = fuzzy_match("mail", "Answer mail").unwrap();
+        let loose = fuzzy_match("mail", "my email").unwrap();
+        assert!(tight.score > loose.score);
+    }
+}
@@ -0,0 +1,2869 @@
+mod archive;
+pub mod backend;
+mod caldav;
+pub mod exchange;
+mod filter;
+mod fuzzy;
+pub mod parse;
+mod reminders;
+pub mod sort;
+mod stats;
+mod taskwarrior;
+mod timetrack;
+mod todotxt;
+mod undo;
+
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use gtk4::prelude::*;
+use gtk4::{
+    gio, Align, Box as GtkBox, Button, CheckButton, DragSource, DropDown, DropTarget, Entry,
+    EventControllerKey, GestureClick, Label, MenuButton, Orientation, Popover, Revealer,
+    RevealerTransitionType, ScrolledWindow, SearchEntry, Separator, Stack, StringList,
+    WidgetPaintable,
+};
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::config::{SortMode, StorageFormat, TodoConfig};
+
+// ─── Data Model ──────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TodoItem {
+    /// Stable identifier; survives reordering and deletion of other items.
+    #[serde(default)]
+    pub id: u64,
+    pub text: String,
+    pub done: bool,
+    pub priority: u8, // 1 (highest) – 9 (lowest), 0 = none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<Due>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<Recurrence>,
+    /// Every time a recurring task was checked off.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub completions: Vec<NaiveDateTime>,
+    /// When a non-recurring task was checked off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<NaiveDateTime>,
+    /// Explicit reminder time from a `remind:` token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remind: Option<NaiveDateTime>,
+    /// From a `+project` token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// From `#tag` tokens.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subtasks: Vec<Subtask>,
+    /// When the task was added.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<NaiveDateTime>,
+    /// Time tracked on the task, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub time_entries: Vec<timetrack::TimeEntry>,
+    /// Work sessions finished with the pomodoro timer attached to the task.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub pomodoros: u32,
+    /// Unrecognised `key:value` pairs from a todo.txt line, kept so they
+    /// survive a rewrite.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extras: Vec<(String, String)>,
+    /// Identifier in an external task store, e.g. a Taskwarrior UUID or a
+    /// CalDAV UID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    /// Pinned as the task the bar shows, whatever the sort order.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub focus: bool,
+    /// Index of the named list the task belongs to. Each list is stored on
+    /// its own, so this is set on load rather than saved.
+    #[serde(skip)]
+    pub list: usize,
+    /// Taskwarrior's urgency score; recomputed on every load.
+    #[serde(skip)]
+    pub urgency: Option<f64>,
+}
+
+/// A checklist entry under a task.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Subtask {
+    pub text: String,
+    pub done: bool,
+}
+
+impl TodoItem {
+    /// Overwrite the entry-editable fields with freshly parsed input, keeping
+    /// identity, completion state and subtasks.
+    pub fn apply(&mut self, parsed: parse::ParsedTask) {
+        self.text = parsed.text;
+        self.priority = parsed.priority;
+        self.due = parsed.due;
+        self.recurrence = parsed.recurrence;
+        self.remind = parsed.remind;
+        self.project = parsed.project;
+        self.tags = parsed.tags;
+    }
+
+    /// When to show a desktop notification: the explicit `remind:` time, or
+    /// the due time for tasks due at a specific time of day.
+    pub fn reminder_at(&self) -> Option<NaiveDateTime> {
+        self.remind
+            .or_else(|| self.due.filter(|d| d.time.is_some()).map(|d| d.deadline()))
+    }
+
+    /// Mark the task as done, or for recurring tasks record the completion
+    /// and move the due date to the next occurrence instead.
+    pub fn complete(&mut self, now: NaiveDateTime) {
+        self.stop_timer(now);
+        let Some(rec) = self.recurrence else {
+            self.done = true;
+            self.completed_at = Some(now);
+            return;
+        };
+
+        self.completions.push(now);
+        let today = now.date();
+        let next = match rec {
+            Recurrence::AfterCompletion(n) => today + Duration::days(n.max(1) as i64),
+            // Skip occurrences already missed so an overdue task lands in
+            // the future rather than on another past date.
+            _ => {
+                let from = self.due.map_or(today, |d| d.date.max(today));
+                rec.next_after(from)
+            }
+        };
+        let next = Due {
+            date: next,
+            time: self.due.and_then(|d| d.time),
+        };
+
+        // Keep an explicit reminder at the same offset from the due date.
+        if let (Some(remind), Some(prev)) = (self.remind, self.due) {
+            self.remind = Some(remind + (next.deadline() - prev.deadline()));
+        }
+        self.due = Some(next);
+        self.done = false;
+
+        // The checklist starts over for the next occurrence.
+        for sub in &mut self.subtasks {
+            sub.done = false;
+        }
+    }
+
+    /// Mark the task as not done again.
+    pub fn reopen(&mut self) {
+        self.done = false;
+        self.completed_at = None;
+    }
+
+    /// (done, total) subtask counts.
+    pub fn subtask_progress(&self) -> (usize, usize) {
+        let done = self.subtasks.iter().filter(|s| s.done).count();
+        (done, self.subtasks.len())
+    }
+
+    /// Check or uncheck a subtask. With `auto_complete` the parent follows:
+    /// it completes when the last subtask is checked and reopens when one is
+    /// unchecked again.
+    pub fn set_subtask_done(
+        &mut self,
+        sub: usize,
+        done: bool,
+        auto_complete: bool,
+        now: NaiveDateTime,
+    ) {
+        let Some(s) = self.subtasks.get_mut(sub) else {
+            return;
+        };
+        s.done = done;
+
+        if !auto_complete {
+            return;
+        }
+        let (checked, total) = self.subtask_progress();
+        if checked == total && !self.done {
+            self.complete(now);
+        } else if checked < total && self.done {
+            self.reopen();
+        }
+    }
+}
+
+/// When a task is due. Tasks without a time are due by the end of the day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Due {
+    pub date: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<NaiveTime>,
+}
+
+impl Due {
+    /// The moment after which the task counts as overdue.
+    pub fn deadline(&self) -> NaiveDateTime {
+        match self.time {
+            Some(t) => self.date.and_time(t),
+            None => self.date.and_hms_opt(23, 59, 59).unwrap_or_default(),
+        }
+    }
+
+    /// Compact label relative to `today`: "Today 09:00", "Tomorrow", "Fri", "24 Oct".
+    pub fn describe(&self, today: NaiveDate) -> String {
+        let day = match (self.date - today).num_days() {
+            0 => "Today".to_string(),
+            1 => "Tomorrow".to_string(),
+            -1 => "Yesterday".to_string(),
+            2..=6 => self.date.format("%a").to_string(),
+            _ if self.date.year() == today.year() => self.date.format("%d %b").to_string(),
+            _ => self.date.format("%d %b %Y").to_string(),
+        };
+        match self.time {
+            Some(t) => format!("{} {}", day, t.format("%H:%M")),
+            None => day,
+        }
+    }
+}
+
+/// How a task repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Recurrence {
+    Daily,
+    /// Monday to Friday.
+    Weekdays,
+    Weekly(Weekday),
+    /// Day of month; clamped to the month's length.
+    Monthly(u32),
+    /// Fixed interval measured from the previous due date.
+    EveryDays(u32),
+    /// Interval measured from when the task was last checked off.
+    AfterCompletion(u32),
+}
+
+impl Recurrence {
+    /// The first occurrence strictly after `date`.
+    pub fn next_after(&self, date: NaiveDate) -> NaiveDate {
+        match *self {
+            Recurrence::Daily => date + Duration::days(1),
+            Recurrence::Weekdays => {
+                let mut d = date + Duration::days(1);
+                while matches!(d.weekday(), Weekday::Sat | Weekday::Sun) {
+                    d += Duration::days(1);
+                }
+                d
+            }
+            Recurrence::Weekly(wd) => parse::next_weekday(date, wd),
+            // Only fails at the end of the calendar, where "never" is right.
+            Recurrence::Monthly(day) => parse::clamp_day(date.year(), date.month(), day)
+                .filter(|d| *d > date)
+                .or_else(|| {
+                    let next = parse::add_months(date.with_day(1)?, 1)?;
+                    parse::clamp_day(next.year(), next.month(), day)
+                })
+                .unwrap_or(NaiveDate::MAX),
+            Recurrence::EveryDays(n) | Recurrence::AfterCompletion(n) => {
+                date + Duration::days(n.max(1) as i64)
+            }
+        }
+    }
+
+    /// The first occurrence on or after `date`.
+    pub fn first_on_or_after(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Recurrence::EveryDays(_) | Recurrence::AfterCompletion(_) => date,
+            _ => self.next_after(date - Duration::days(1)),
+        }
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "every day"),
+            Recurrence::Weekdays => write!(f, "every weekday"),
+            Recurrence::Weekly(wd) => write!(f, "every {}", wd),
+            Recurrence::Monthly(day) => write!(f, "monthly on the {}", ordinal(*day)),
+            Recurrence::EveryDays(1) => write!(f, "every day"),
+            Recurrence::EveryDays(n) => write!(f, "every {} days", n),
+            Recurrence::AfterCompletion(1) => write!(f, "a day after completion"),
+            Recurrence::AfterCompletion(n) => write!(f, "{} days after completion", n),
+        }
+    }
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (1, 11) | (2, 12) | (3, 13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+pub struct TodoStore {
+    /// Tasks of every list; see [`TodoItem::list`].
+    pub items: Vec<TodoItem>,
+    history: undo::UndoStack,
+    /// Named lists, each with its own storage.
+    lists: Vec<(String, Box<dyn backend::TodoBackend>)>,
+    /// The list shown in the popover and on the bar, and where new tasks go.
+    pub active: usize,
+}
+
+impl TodoStore {
+    /// Read every list. Errors are logged and leave that list empty.
+    pub fn load(lists: Vec<(String, Box<dyn backend::TodoBackend>)>) -> Self {
+        let mut store = Self {
+            items: Vec::new(),
+            history: undo::UndoStack::default(),
+            lists,
+            active: 0,
+        };
+        store.items = store.load_items();
+        store.assign_ids();
+        store
+    }
+
+    /// Open the lists configured in `cfg`, starting on its default list.
+    pub fn open(cfg: &TodoConfig) -> Self {
+        let mut store = Self::load(backend::lists_from_config(cfg));
+        if let Some(ref name) = cfg.default_list {
+            match store.list_index(name) {
+                Some(list) => store.active = list,
+                None => log::warn!("default_list {name:?} is not one of the configured lists"),
+            }
+        }
+        store
+    }
+
+    /// Items of all lists, with ids that clash with an earlier list cleared
+    /// so `assign_ids` gives them fresh ones.
+    fn load_items(&self) -> Vec<TodoItem> {
+        let mut items: Vec<TodoItem> = Vec::new();
+        let mut seen = HashSet::new();
+        for list in 0..self.lists.len() {
+            items.extend(self.load_list(list).into_iter().map(|mut item| {
+                if !seen.insert(item.id) {
+                    item.id = 0;
+                }
+                item
+            }));
+        }
+        items
+    }
+
+    /// Items of `list`. Errors are logged and leave it empty.
+    fn load_list(&self, list: usize) -> Vec<TodoItem> {
+        let Some((_, backend)) = self.lists.get(list) else {
+            return Vec::new();
+        };
+        let loaded = backend.load().unwrap_or_else(|e| {
+            log::error!(
+                "Failed to load tasks from {}: {e:#}",
+                backend.path().display()
+            );
+            Vec::new()
+        });
+        loaded
+            .into_iter()
+            .map(|item| TodoItem { list, ..item })
+            .collect()
+    }
+
+    /// Call `f` with a list's index whenever its backend has finished
+    /// loading it in the background.
+    pub fn on_background_load(&self, f: impl Fn(usize) + 'static) {
+        let f = Rc::new(f);
+        for (list, (_, backend)) in self.lists.iter().enumerate() {
+            let f = Rc::clone(&f);
+            backend.on_loaded(Box::new(move || f(list)));
+        }
+    }
+
+    /// Re-read `list` after a background load. Unlike [`Self::reload`] this
+    /// is no undo step: undoing a first load would delete every task.
+    pub fn refill(&mut self, list: usize) {
+        let loaded = self.load_list(list);
+        self.items.retain(|t| t.list != list);
+        let taken: HashSet<u64> = self.items.iter().map(|t| t.id).collect();
+        self.items.extend(loaded.into_iter().map(|item| TodoItem {
+            id: if taken.contains(&item.id) { 0 } else { item.id },
+            ..item
+        }));
+        self.assign_ids();
+    }
+
+    pub fn save(&mut self) {
+        for (list, (_, backend)) in self.lists.iter().enumerate() {
+            let positions: Vec<usize> = (0..self.items.len())
+                .filter(|&i| self.items[i].list == list)
+                .collect();
+            let mut part: Vec<TodoItem> =
+                positions.iter().map(|&i| self.items[i].clone()).collect();
+            if let Err(e) = backend.save(&mut part) {
+                log::error!(
+                    "Failed to save tasks to {}: {e:#}",
+                    backend.path().display()
+                );
+            }
+            // Backends may have recorded external ids.
+            for (i, item) in positions.into_iter().zip(part) {
+                self.items[i] = item;
+            }
+        }
+    }
+
+    /// Names of the lists, in configured order.
+    pub fn list_names(&self) -> impl Iterator<Item = &str> {
+        self.lists.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Index of the list called `name`, ignoring case.
+    pub fn list_index(&self, name: &str) -> Option<usize> {
+        self.lists
+            .iter()
+            .position(|(n, _)| n.eq_ignore_ascii_case(name))
+    }
+
+    /// Tasks of the active list, in store order.
+    pub fn active_items(&self) -> impl Iterator<Item = &TodoItem> {
+        self.items.iter().filter(|t| t.list == self.active)
+    }
+
+    /// Move task `id` to `list` as one undoable step. Returns false, with
+    /// nothing recorded, if either does not exist.
+    pub fn move_to_list(&mut self, id: u64, list: usize) -> bool {
+        if list >= self.lists.len() || self.find(id).is_none() {
+            return false;
+        }
+        self.checkpoint();
+        let Some(item) = self.find_mut(id) else {
+            return false;
+        };
+        item.list = list;
+        // A pin belongs to the list the task came from, and an external id
+        // to its storage.
+        item.focus = false;
+        item.external_id = None;
+        true
+    }
+
+    /// Give every item without an id (e.g. from older files) a fresh one.
+    fn assign_ids(&mut self) {
+        let fresh = self.next_id()..;
+        for (id, item) in fresh.zip(self.items.iter_mut().filter(|t| t.id == 0)) {
+            item.id = id;
+        }
+    }
+
+    /// An id not used by any current item.
+    pub fn next_id(&self) -> u64 {
+        self.items.iter().map(|t| t.id).max().unwrap_or(0) + 1
+    }
+
+    pub fn find(&self, id: u64) -> Option<&TodoItem> {
+        self.items.iter().find(|t| t.id == id)
+    }
+
+    pub fn find_mut(&mut self, id: u64) -> Option<&mut TodoItem> {
+        self.items.iter_mut().find(|t| t.id == id)
+    }
+
+    /// Append a task written in entry syntax ("3:Deploy server tomorrow").
+    /// Returns its id.
+    pub fn add(&mut self, input: &str, now: NaiveDateTime) -> u64 {
+        let mut item = TodoItem {
+            id: self.next_id(),
+            created: Some(now),
+            list: self.active,
+            ..Default::default()
+        };
+        item.apply(parse::parse_task(input, now));
+        let id = item.id;
+        self.items.push(item);
+        id
+    }
+
+    /// Append imported tasks to the active list, giving them fresh ids.
+    /// Returns how many.
+    pub fn append(&mut self, items: Vec<TodoItem>) -> usize {
+        let count = items.len();
+        let list = self.active;
+        self.items
+            .extend(items.into_iter().map(|t| TodoItem { id: 0, list, ..t }));
+        self.assign_ids();
+        count
+    }
+
+    /// Current store index of task `id`.
+    pub fn position(&self, id: u64) -> Option<usize> {
+        self.items.iter().position(|t| t.id == id)
+    }
+
+    pub fn remove(&mut self, id: u64) -> Option<TodoItem> {
+        let idx = self.position(id)?;
+        Some(self.items.remove(idx))
+    }
+
+    /// Re-read the list after another process changed it. The undo history
+    /// is kept, so the outside change can be undone like a local one.
+    pub fn reload(&mut self) {
+        self.checkpoint();
+        self.items = self.load_items();
+        self.assign_ids();
+    }
+
+    /// Snapshot the list before a mutation so it can be undone.
+    pub fn checkpoint(&mut self) {
+        self.history.record(&self.items);
+    }
+
+    /// Carry a change that is not the user's (a sync merge) into the undo
+    /// history; see [`undo::UndoStack::rebase`].
+    pub fn rebase_history(&mut self, change: impl FnMut(&mut Vec<TodoItem>)) {
+        self.history.rebase(change);
+    }
+
+    /// Note that the step just checkpointed also changed the archive, so
+    /// undo and redo replay that too.
+    pub fn checkpoint_archive(&mut self, change: undo::ArchiveMove) {
+        self.history.record_archive(change);
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let change = self.history.undo(&mut self.items);
+        self.replay(change)
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let change = self.history.redo(&mut self.items);
+        self.replay(change)
+    }
+
+    fn replay(&self, change: Option<undo::ArchiveMove>) -> bool {
+        let Some(change) = change else {
+            return false;
+        };
+        let lists: Vec<&str> = self.list_names().collect();
+        if let Err(e) = archive::Archive::apply(&change, &lists) {
+            log::error!("Failed to update the archive: {e:#}");
+        }
+        true
+    }
+
+    /// Index of the closest item above or below `idx` in the same list.
+    pub fn neighbour(&self, idx: usize, up: bool) -> Option<usize> {
+        let list = self.items.get(idx)?.list;
+        let same = |&i: &usize| self.items[i].list == list;
+        if up {
+            (0..idx).rev().find(same)
+        } else {
+            (idx + 1..self.items.len()).find(same)
+        }
+    }
+
+    /// Move the item at `from` so it lands at index `to` (clamped).
+    pub fn move_item(&mut self, from: usize, to: usize) {
+        if from >= self.items.len() {
+            return;
+        }
+        let item = self.items.remove(from);
+        let to = to.min(self.items.len());
+        self.items.insert(to, item);
+    }
+
+    /// Remove and return every completed item matching `pred`.
+    pub fn take_done(&mut self, pred: impl Fn(&TodoItem) -> bool) -> Vec<TodoItem> {
+        let (taken, kept) = std::mem::take(&mut self.items)
+            .into_iter()
+            .partition(|t| t.done && pred(t));
+        self.items = kept;
+        taken
+    }
+
+    /// Move completed items past the configured age into the archive.
+    /// Returns whether anything was moved.
+    pub fn auto_archive(&mut self, cfg: &TodoConfig) -> bool {
+        let now = Local::now().naive_local();
+        self.archive_done(|t| archive::is_expired(t, cfg, now))
+            .is_ok_and(|n| n > 0)
+    }
+
+    /// Move every completed item matching `pred` into the archive as one
+    /// undoable step. Returns how many were moved; nothing changes if the
+    /// archive cannot be written.
+    pub fn archive_done(&mut self, pred: impl Fn(&TodoItem) -> bool) -> anyhow::Result<usize> {
+        let done: Vec<TodoItem> = self
+            .items
+            .iter()
+            .filter(|t| t.done && pred(t))
+            .cloned()
+            .collect();
+        if done.is_empty() {
+            return Ok(0);
+        }
+        let lists: Vec<&str> = self.list_names().collect();
+        if let Err(e) = archive::Archive::append(done.clone(), &lists) {
+            log::error!("Failed to archive tasks: {e:#}");
+            return Err(e);
+        }
+        self.checkpoint();
+        self.take_done(pred);
+        let n = done.len();
+        self.checkpoint_archive(undo::ArchiveMove {
+            archived: done,
+            restored: Vec::new(),
+        });
+        self.save();
+        Ok(n)
+    }
+
+    /// Drop archive entries that are also in the live list, left behind if
+    /// an undo could not rewrite the archive. Run once at startup.
+    pub fn prune_archive(&self) {
+        let Ok(mut archive) = archive::Archive::load() else {
+            return;
+        };
+        if archive.prune(self) {
+            if let Err(e) = archive.save() {
+                log::error!("{e:#}");
+            }
+        }
+    }
+
+    /// Number of incomplete items in the active list.
+    pub fn pending_count(&self) -> usize {
+        self.active_items().filter(|t| !t.done).count()
+    }
+
+    /// The task shown on the bar: the active list's pinned focus if it is
+    /// still pending, otherwise its first incomplete item in `mode` order.
+    pub fn top_task(&self, mode: SortMode) -> Option<&str> {
+        let focus = self.active_items().find(|t| t.focus && !t.done);
+        let first = || {
+            sort::order(&self.items, mode, Local::now().naive_local())
+                .into_iter()
+                .map(|i| &self.items[i])
+                .find(|t| !t.done && t.list == self.active)
+        };
+        focus.or_else(first).map(|t| t.text.as_str())
+    }
+
+    /// Start tracking time on `id`, stopping whatever timer was running.
+    pub fn start_timer(&mut self, id: u64, now: NaiveDateTime) {
+        for item in &mut self.items {
+            item.stop_timer(now);
+        }
+        if let Some(item) = self.find_mut(id) {
+            item.time_entries.push(timetrack::TimeEntry {
+                start: now,
+                end: None,
+            });
+        }
+    }
+
+    /// The task whose timer is running and when it started.
+    pub fn running_timer(&self) -> Option<(&TodoItem, NaiveDateTime)> {
+        self.items
+            .iter()
+            .find_map(|t| t.timer_started().map(|at| (t, at)))
+    }
+
+    /// Pin `id` as its list's focus, or unpin it if it already is.
+    pub fn toggle_focus(&mut self, id: u64) {
+        let Some(list) = self.find(id).map(|t| t.list) else {
+            return;
+        };
+        for item in self.items.iter_mut().filter(|t| t.list == list) {
+            item.focus = item.id == id && !item.focus;
+        }
+    }
+}
+
+// ─── Widget Construction ─────────────────────────────────────────────────────
+
+/// Type alias for the refresh callback wrapped in Rc<RefCell<Option<...>>>.
+type RefreshCallback = Rc<RefCell<Option<Box<dyn Fn()>>>>;
+
+/// Application action that makes a running bar re-read the task list after
+/// `zenith todo add/done/rm` changed it.
+pub const RELOAD_ACTION: &str = "todo-reload";
+
+/// State shared by every row in the popover list.
+struct RowContext {
+    store: Rc<RefCell<TodoStore>>,
+    refresh: RefreshCallback,
+    /// Ids of tasks whose subtask section is expanded.
+    expanded: RefCell<HashSet<u64>>,
+    toast: Toast,
+    cfg: TodoConfig,
+    /// Current list order; reordering by hand only works in manual mode.
+    sort: Cell<SortMode>,
+    /// Whether the filter or search hides some tasks. Moving a row next to
+    /// its visible neighbour would then jump over the hidden ones.
+    narrowed: Cell<bool>,
+    /// Id of the task whose row last had keyboard focus.
+    cursor: Cell<Option<u64>>,
+    /// Up from the first row lands back in the search field.
+    search: glib::WeakRef<SearchEntry>,
+}
+
+impl RowContext {
+    /// Swap task `id` with the one above or below it in its list.
+    fn swap(&self, id: u64, up: bool) {
+        let mut s = self.store.borrow_mut();
+        let idx = s.position(id);
+        if let Some((idx, other)) = idx.and_then(|i| Some((i, s.neighbour(i, up)?))) {
+            s.checkpoint();
+            s.items.swap(idx, other);
+            s.save();
+        }
+        drop(s);
+        self.refresh();
+    }
+
+    /// Rows can only be moved by hand in manual order with nothing hidden.
+    fn can_reorder(&self) -> bool {
+        self.sort.get() == SortMode::Manual && !self.narrowed.get()
+    }
+
+    fn refresh(&self) {
+        if let Some(ref f) = *self.refresh.borrow() {
+            f();
+        }
+    }
+
+    /// Step the store back (or forward) one change and repaint.
+    fn undo(&self, redo: bool) {
+        self.toast.hide();
+        let mut s = self.store.borrow_mut();
+        let changed = if redo { s.redo() } else { s.undo() };
+        if changed {
+            s.save();
+        }
+        drop(s);
+        if changed {
+            self.refresh();
+        }
+    }
+}
+
+/// How long the "Task deleted — Undo" row stays visible.
+const TOAST_SECS: u32 = 5;
+
+/// Transient message row at the bottom of the list with an Undo button.
+struct Toast {
+    revealer: Revealer,
+    label: Label,
+    undo_btn: Button,
+    timer: Rc<RefCell<Option<glib::SourceId>>>,
+}
+
+impl Toast {
+    fn new() -> Self {
+        let bar = GtkBox::new(Orientation::Horizontal, 8);
+        bar.add_css_class("zenith-todo-toast");
+
+        let label = Label::new(None);
+        label.set_hexpand(true);
+        label.set_halign(Align::Start);
+        label.add_css_class("zenith-todo-toast-text");
+        bar.append(&label);
+
+        let undo_btn = Button::with_label("Undo");
+        undo_btn.add_css_class("zenith-todo-toast-btn");
+        bar.append(&undo_btn);
+
+        let revealer = Revealer::new();
+        revealer.set_transition_type(RevealerTransitionType::SlideUp);
+        revealer.set_child(Some(&bar));
+
+        Self {
+            revealer,
+            label,
+            undo_btn,
+            timer: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    fn show(&self, message: &str) {
+        self.label.set_label(&format!("{} —", message));
+        self.undo_btn.set_visible(true);
+        self.reveal();
+    }
+
+    /// A message without the Undo button.
+    fn notice(&self, message: &str) {
+        self.label.set_label(message);
+        self.undo_btn.set_visible(false);
+        self.reveal();
+    }
+
+    fn reveal(&self) {
+        self.revealer.set_reveal_child(true);
+
+        if let Some(old) = self.timer.borrow_mut().take() {
+            old.remove();
+        }
+        let revealer = self.revealer.downgrade();
+        let timer = Rc::clone(&self.timer);
+        let source = glib::timeout_add_seconds_local_once(TOAST_SECS, move || {
+            timer.borrow_mut().take();
+            if let Some(r) = revealer.upgrade() {
+                r.set_reveal_child(false);
+            }
+        });
+        *self.timer.borrow_mut() = Some(source);
+    }
+
+    fn hide(&self) {
+        if let Some(old) = self.timer.borrow_mut().take() {
+            old.remove();
+        }
+        self.revealer.set_reveal_child(false);
+    }
+}
+
+/// Handle through which other bar modules read and annotate the task list.
+#[derive(Clone)]
+pub struct Tasks {
+    store: Rc<RefCell<TodoStore>>,
+    refresh: RefreshCallback,
+}
+
+impl Tasks {
+    /// `(id, text)` of every pending task, in list order.
+    pub fn pending(&self) -> Vec<(u64, String)> {
+        self.store
+            .borrow()
+            .items
+            .iter()
+            .filter(|t| !t.done)
+            .map(|t| (t.id, t.text.clone()))
+            .collect()
+    }
+
+    /// Text of task `id` while it is pending.
+    pub fn text(&self, id: u64) -> Option<String> {
+        self.store
+            .borrow()
+            .find(id)
+            .filter(|t| !t.done)
+            .map(|t| t.text.clone())
+    }
+
+    /// The task pinned as the bar's focus, if any.
+    pub fn focus(&self) -> Option<u64> {
+        self.store
+            .borrow()
+            .active_items()
+            .find(|t| t.focus && !t.done)
+            .map(|t| t.id)
+    }
+
+    /// Count a finished pomodoro on task `id`.
+    ///
+    /// The timer, not the user, makes this change, so it takes no undo step
+    /// of its own. It goes into every snapshot instead: undoing an edit
+    /// made before the session never takes the count back.
+    pub fn add_pomodoro(&self, id: u64) {
+        let mut s = self.store.borrow_mut();
+        let Some(item) = s.find_mut(id) else {
+            return;
+        };
+        item.pomodoros += 1;
+        s.rebase_history(|snapshot| {
+            if let Some(item) = snapshot.iter_mut().find(|t| t.id == id) {
+                item.pomodoros += 1;
+            }
+        });
+        s.save();
+        drop(s);
+        if let Some(ref f) = *self.refresh.borrow() {
+            f();
+        }
+    }
+}
+
+/// Create the todo bar widget: a button that shows the top task or "+" when empty.
+pub fn create(cfg: &TodoConfig) -> (GtkBox, Tasks) {
+    let store = Rc::new(RefCell::new(TodoStore::open(cfg)));
+    store.borrow().prune_archive();
+
+    let container = GtkBox::new(Orientation::Horizontal, 0);
+    container.set_halign(Align::Start);
+
+    // ── Bar Button ───────────────────────────────────────────────────
+    let bar_btn = Button::new();
+    bar_btn.add_css_class("zenith-todo-btn");
+    bar_btn.add_css_class("zenith-module");
+    bar_btn.add_css_class("zenith-module-left");
+    container.append(&bar_btn);
+
+    // ── Popover ──────────────────────────────────────────────────────
+    let popover = Popover::new();
+    popover.set_autohide(true);
+    popover.set_cascade_popdown(true);
+    popover.set_has_arrow(false);
+    popover.set_position(gtk4::PositionType::Bottom);
+    popover.add_css_class("zenith-todo-popup");
+    popover.set_parent(&bar_btn);
+
+    // ── Popover Content ──────────────────────────────────────────────
+    let pop_box = GtkBox::new(Orientation::Vertical, 0);
+    pop_box.set_width_request(320);
+    pop_box.add_css_class("zenith-todo-container");
+
+    // Header
+    let header = GtkBox::new(Orientation::Horizontal, 8);
+    header.add_css_class("zenith-todo-header");
+    let title = Label::new(Some("  Tasks"));
+    title.add_css_class("zenith-todo-title");
+    title.set_hexpand(true);
+    title.set_halign(Align::Start);
+    header.append(&title);
+
+    // With several lists, a switcher takes the title's place.
+    let names: Vec<String> = store.borrow().list_names().map(str::to_string).collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    let list_dd = DropDown::from_strings(&names);
+    list_dd.set_selected(store.borrow().active as u32);
+    list_dd.set_hexpand(true);
+    list_dd.set_halign(Align::Start);
+    list_dd.add_css_class("zenith-todo-list-switch");
+    list_dd.set_tooltip_text(Some("Task list"));
+    if names.len() > 1 {
+        title.set_visible(false);
+        header.append(&list_dd);
+    }
+
+    // Progress badge in header
+    let progress_label = Label::new(None);
+    progress_label.add_css_class("zenith-todo-progress");
+    header.append(&progress_label);
+
+    // Archive actions
+    let clear_btn = Button::with_label("Clear");
+    clear_btn.add_css_class("zenith-todo-header-btn");
+    clear_btn.set_tooltip_text(Some("Archive completed tasks"));
+    header.append(&clear_btn);
+    pop_box.append(&header);
+
+    // Progress bar
+    let progress_bar = GtkBox::new(Orientation::Horizontal, 0);
+    progress_bar.add_css_class("zenith-todo-progress-track");
+    let progress_fill = GtkBox::new(Orientation::Horizontal, 0);
+    progress_fill.add_css_class("zenith-todo-progress-fill");
+    progress_bar.append(&progress_fill);
+    pop_box.append(&progress_bar);
+
+    // Search field, focused with "/"
+    let search = SearchEntry::new();
+    search.set_placeholder_text(Some("Search tasks  ( / )"));
+    search.add_css_class("zenith-todo-search");
+    pop_box.append(&search);
+
+    // Filter bar: project/tag, priority band, done/pending
+    let filter_bar = GtkBox::new(Orientation::Horizontal, 4);
+    filter_bar.add_css_class("zenith-todo-filter-bar");
+
+    let scope_model = StringList::new(&[&filter::Scope::All.to_string()]);
+    let scope_dd = DropDown::new(Some(scope_model.clone()), None::<gtk4::Expression>);
+    let band_dd = DropDown::from_strings(&filter::BAND_LABELS);
+    let status_dd = DropDown::from_strings(&filter::STATUS_LABELS);
+    let sort_dd = DropDown::from_strings(&sort::LABELS);
+    sort_dd.set_selected(sort::index_of(cfg.sort));
+    sort_dd.set_tooltip_text(Some("Sort order"));
+    for dd in [&scope_dd, &band_dd, &status_dd, &sort_dd] {
+        dd.set_hexpand(true);
+        dd.add_css_class("zenith-todo-filter");
+        filter_bar.append(dd);
+    }
+    pop_box.append(&filter_bar);
+
+    let sep = Separator::new(Orientation::Horizontal);
+    sep.add_css_class("zenith-todo-sep");
+    pop_box.append(&sep);
+
+    // Scrollable task list
+    let scroll = ScrolledWindow::new();
+    scroll.set_vexpand(true);
+    scroll.set_min_content_height(60);
+    scroll.set_max_content_height(280);
+    scroll.set_propagate_natural_height(true);
+    scroll.add_css_class("zenith-todo-scroll");
+
+    let list_box = GtkBox::new(Orientation::Vertical, 2);
+    list_box.add_css_class("zenith-todo-list");
+    scroll.set_child(Some(&list_box));
+
+    // Archived tasks, swapped in for the list by the header button
+    let archive_scroll = ScrolledWindow::new();
+    archive_scroll.set_vexpand(true);
+    archive_scroll.set_min_content_height(60);
+    archive_scroll.set_max_content_height(280);
+    archive_scroll.set_propagate_natural_height(true);
+    archive_scroll.add_css_class("zenith-todo-scroll");
+
+    let archive_list = GtkBox::new(Orientation::Vertical, 2);
+    archive_list.add_css_class("zenith-todo-list");
+    archive_scroll.set_child(Some(&archive_list));
+
+    // Time tracking summary
+    let time_scroll = ScrolledWindow::new();
+    time_scroll.set_vexpand(true);
+    time_scroll.set_min_content_height(60);
+    time_scroll.set_max_content_height(280);
+    time_scroll.set_propagate_natural_height(true);
+    time_scroll.add_css_class("zenith-todo-scroll");
+
+    let time_list = GtkBox::new(Orientation::Vertical, 2);
+    time_list.add_css_class("zenith-todo-list");
+    time_scroll.set_child(Some(&time_list));
+
+    // Completion statistics
+    let stats_scroll = ScrolledWindow::new();
+    stats_scroll.set_vexpand(true);
+    stats_scroll.set_min_content_height(60);
+    stats_scroll.set_max_content_height(280);
+    stats_scroll.set_propagate_natural_height(true);
+    stats_scroll.add_css_class("zenith-todo-scroll");
+
+    let stats_list = GtkBox::new(Orientation::Vertical, 6);
+    stats_list.add_css_class("zenith-todo-list");
+    stats_scroll.set_child(Some(&stats_list));
+
+    let stack = Stack::new();
+    stack.set_vhomogeneous(false);
+    stack.add_named(&scroll, Some("tasks"));
+    stack.add_named(&archive_scroll, Some("archive"));
+    stack.add_named(&time_scroll, Some("time"));
+    stack.add_named(&stats_scroll, Some("stats"));
+    pop_box.append(&stack);
+
+    // "Task deleted — Undo" toast, revealed under the list
+    let toast = Toast::new();
+    pop_box.append(&toast.revealer);
+
+    // ── Input Row ────────────────────────────────────────────────────
+    let sep2 = Separator::new(Orientation::Horizontal);
+    sep2.add_css_class("zenith-todo-sep");
+    pop_box.append(&sep2);
+
+    // Live preview of the metadata parsed from the entry text
+    let preview = Label::new(None);
+    preview.set_halign(Align::Start);
+    preview.set_visible(false);
+    preview.add_css_class("zenith-todo-preview");
+    pop_box.append(&preview);
+
+    let input_row = GtkBox::new(Orientation::Horizontal, 6);
+    input_row.add_css_class("zenith-todo-input-row");
+
+    let entry = Entry::new();
+    entry.set_placeholder_text(Some("Add a task…"));
+    entry.set_hexpand(true);
+    entry.add_css_class("zenith-todo-entry");
+    input_row.append(&entry);
+
+    let add_btn = Button::with_label("+");
+    add_btn.add_css_class("zenith-todo-add-btn");
+    input_row.append(&add_btn);
+
+    pop_box.append(&input_row);
+    popover.set_child(Some(&pop_box));
+
+    // ── State Refresh Closures ───────────────────────────────────────
+    let store_rc = Rc::clone(&store);
+    let bar_btn_weak = bar_btn.downgrade();
+    let list_box_rc = Rc::new(list_box);
+    let progress_label_rc = Rc::new(progress_label);
+    let progress_fill_rc = Rc::new(progress_fill);
+    let filter_state = Rc::new(RefCell::new(filter::TodoFilter::default()));
+    let scope_options = Rc::new(RefCell::new(vec![filter::Scope::All]));
+    // Set while the scope dropdown model is rebuilt so the resulting
+    // selection change does not trigger a nested refresh.
+    let updating_scopes = Rc::new(Cell::new(false));
+
+    // This closure rebuilds the full list and bar label from the current store.
+    let refresh: RefreshCallback = Rc::new(RefCell::new(None));
+
+    // Optional CalDAV sync, with its status next to the progress badge.
+    let caldav = match cfg.caldav {
+        Some(_) if cfg.format == StorageFormat::Taskwarrior => {
+            log::warn!("CalDAV sync is not available with the taskwarrior format");
+            None
+        }
+        Some(ref dav) => caldav::CaldavSync::new(dav, Rc::clone(&store_rc), Rc::clone(&refresh)),
+        None => None,
+    };
+    if let Some(ref sync) = caldav {
+        header.insert_child_after(sync.indicator(), Some(&*progress_label_rc));
+    }
+
+    let row_ctx = Rc::new(RowContext {
+        store: Rc::clone(&store_rc),
+        refresh: Rc::clone(&refresh),
+        expanded: RefCell::new(HashSet::new()),
+        toast,
+        cfg: cfg.clone(),
+        sort: Cell::new(cfg.sort),
+        narrowed: Cell::new(false),
+        cursor: Cell::new(None),
+        search: search.downgrade(),
+    });
+
+    header.append(&build_exchange_menu(&row_ctx));
+
+    row_ctx.toast.undo_btn.connect_clicked({
+        let ctx = Rc::clone(&row_ctx);
+        move |_| ctx.undo(false)
+    });
+
+    // Popover-wide keys. Row keys are handled on the rows themselves.
+    let undo_keys = EventControllerKey::new();
+    undo_keys.connect_key_pressed({
+        let ctx = Rc::clone(&row_ctx);
+        let search = search.downgrade();
+        let popover = popover.downgrade();
+        move |_, key, _, state| {
+            // "/" jumps to the search field and Escape closes the popover.
+            if !state.contains(gdk4::ModifierType::CONTROL_MASK) {
+                match key {
+                    gdk4::Key::slash => {
+                        if let Some(search) = search.upgrade() {
+                            search.grab_focus();
+                        }
+                    }
+                    gdk4::Key::Escape => {
+                        if let Some(popover) = popover.upgrade() {
+                            popover.popdown();
+                        }
+                    }
+                    _ => return glib::Propagation::Proceed,
+                }
+                return glib::Propagation::Stop;
+            }
+            // Ctrl+Z / Ctrl+Shift+Z (or Ctrl+Y) anywhere in the popover.
+            // Entries handle their own text undo first.
+            let shift = state.contains(gdk4::ModifierType::SHIFT_MASK);
+            match key.to_lower() {
+                gdk4::Key::z => ctx.undo(shift),
+                gdk4::Key::y => ctx.undo(true),
+                _ => return glib::Propagation::Proceed,
+            }
+            glib::Propagation::Stop
+        }
+    });
+    popover.add_controller(undo_keys);
+
+    // Reminder timers are re-armed after every refresh.
+    let reminders = reminders::Reminders::new(Rc::clone(&store_rc), Rc::clone(&refresh));
+    // So is the tick of a running task timer.
+    let timer_tick = Rc::new(TimerTick {
+        store: Rc::clone(&store_rc),
+        bar_btn: bar_btn_weak.clone(),
+        source: RefCell::new(None),
+    });
+
+    let store_for_refresh = Rc::clone(&store_rc);
+    let bar_btn_for_refresh = bar_btn_weak.clone();
+    let list_box_for_refresh = Rc::clone(&list_box_rc);
+    let progress_label_for_refresh = Rc::clone(&progress_label_rc);
+    let progress_fill_for_refresh = Rc::clone(&progress_fill_rc);
+    let filter_for_refresh = Rc::clone(&filter_state);
+    let scope_options_for_refresh = Rc::clone(&scope_options);
+    let scope_dd_for_refresh = scope_dd.downgrade();
+    let updating_scopes_for_refresh = Rc::clone(&updating_scopes);
+    let row_ctx_for_refresh = Rc::clone(&row_ctx);
+    let stack_for_refresh = stack.downgrade();
+    let archive_list_for_refresh = archive_list.downgrade();
+    let time_list_for_refresh = time_list.downgrade();
+    let stats_list_for_refresh = stats_list.downgrade();
+    let caldav_for_refresh = caldav.clone();
+
+    let build_refresh = move || {
+        let store = Rc::clone(&store_for_refresh);
+        let bar_btn_w = bar_btn_for_refresh.clone();
+        let list_box = Rc::clone(&list_box_for_refresh);
+        let prog_lbl = Rc::clone(&progress_label_for_refresh);
+        let prog_fill = Rc::clone(&progress_fill_for_refresh);
+        let row_ctx = Rc::clone(&row_ctx_for_refresh);
+        let reminders = Rc::clone(&reminders);
+        let timer_tick = Rc::clone(&timer_tick);
+        let filter_state = Rc::clone(&filter_for_refresh);
+        let scope_options = Rc::clone(&scope_options_for_refresh);
+        let scope_model = scope_model.clone();
+        let scope_dd = scope_dd_for_refresh.clone();
+        let updating_scopes = Rc::clone(&updating_scopes_for_refresh);
+        let stack = stack_for_refresh.clone();
+        let archive_list = archive_list_for_refresh.clone();
+        let time_list = time_list_for_refresh.clone();
+        let stats_list = stats_list_for_refresh.clone();
+        let caldav = caldav_for_refresh.clone();
+
+        Box::new(move || {
+            let s = store.borrow();
+
+            // ── Update bar button ────────────────────────────────
+            if let Some(btn) = bar_btn_w.upgrade() {
+                if s.active_items().next().is_none() {
+                    btn.set_label(" ");
+                    btn.remove_css_class("zenith-todo-btn-active");
+                    btn.remove_css_class("zenith-todo-btn-urgent");
+                    btn.add_css_class("zenith-todo-btn-empty");
+                } else {
+                    let pending = s.pending_count();
+                    let top = s
+                        .top_task(row_ctx.sort.get())
+                        .unwrap_or("All done ✓")
+                        .chars()
+                        .take(28)
+                        .collect::<String>();
+
+                    let label = if pending == 0 {
+                        format!("✓ {}", top)
+                    } else {
+                        format!(" {} [{}]", top, pending)
+                    };
+                    btn.set_label(&label);
+
+                    btn.remove_css_class("zenith-todo-btn-empty");
+                    btn.remove_css_class("zenith-todo-btn-urgent");
+                    btn.remove_css_class("zenith-todo-btn-active");
+
+                    if pending == 0 {
+                        btn.add_css_class("zenith-todo-btn-active");
+                    } else if pending >= 5 {
+                        btn.add_css_class("zenith-todo-btn-urgent");
+                    } else {
+                        btn.add_css_class("zenith-todo-btn-active");
+                    }
+                }
+
+                // A running timer takes over the label.
+                match timer_label(&s, Local::now().naive_local()) {
+                    Some(label) => {
+                        btn.set_label(&label);
+                        btn.add_css_class("zenith-todo-btn-timing");
+                    }
+                    None => btn.remove_css_class("zenith-todo-btn-timing"),
+                }
+            }
+
+            // ── Update progress label & bar ──────────────────────
+            let total = s.active_items().count();
+            let done = s.active_items().filter(|t| t.done).count();
+            prog_lbl.set_label(&format!("{}/{}", done, total));
+
+            let pct = if total > 0 {
+                (done as f64 / total as f64) * 100.0
+            } else {
+                0.0
+            };
+            // We use margin-end trick: fill goes full width, we clip via CSS
+            // Actually, set a size-request proportional fraction of 300px track
+            let fill_px = ((pct / 100.0) * 296.0) as i32;
+            prog_fill.set_width_request(fill_px.max(0));
+
+            // Color the progress fill based on completion
+            prog_fill.remove_css_class("zenith-todo-fill-low");
+            prog_fill.remove_css_class("zenith-todo-fill-mid");
+            prog_fill.remove_css_class("zenith-todo-fill-high");
+            if pct >= 75.0 {
+                prog_fill.add_css_class("zenith-todo-fill-high");
+            } else if pct >= 40.0 {
+                prog_fill.add_css_class("zenith-todo-fill-mid");
+            } else {
+                prog_fill.add_css_class("zenith-todo-fill-low");
+            }
+
+            // ── Sync project/tag choices ─────────────────────────
+            let options = filter::scope_options(s.active_items());
+            if *scope_options.borrow() != options {
+                let mut f = filter_state.borrow_mut();
+                let selected = options.iter().position(|o| *o == f.scope).unwrap_or(0);
+                if selected == 0 {
+                    f.scope = filter::Scope::All;
+                }
+                drop(f);
+
+                let labels: Vec<String> = options.iter().map(|o| o.to_string()).collect();
+                let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+                updating_scopes.set(true);
+                scope_model.splice(0, scope_model.n_items(), &labels);
+                if let Some(dd) = scope_dd.upgrade() {
+                    dd.set_selected(selected as u32);
+                }
+                updating_scopes.set(false);
+                *scope_options.borrow_mut() = options;
+            }
+
+            // ── Rebuild list ─────────────────────────────────────
+            // Keyboard focus stays on the same task across the rebuild, or
+            // on the same position once that task is gone.
+            let focused_pos = list_box.focus_child().and_then(|focused| {
+                let mut child = list_box.first_child();
+                let mut pos = 0;
+                while let Some(c) = child {
+                    if c == focused {
+                        return Some(pos);
+                    }
+                    child = c.next_sibling();
+                    pos += 1;
+                }
+                None
+            });
+
+            // Remove all children
+            while let Some(child) = list_box.first_child() {
+                list_box.remove(&child);
+            }
+
+            let f = filter_state.borrow();
+            row_ctx.narrowed.set(f.is_narrowed());
+            let order = sort::order(&s.items, row_ctx.sort.get(), Local::now().naive_local());
+            let mut items_snapshot: Vec<(TodoItem, Vec<usize>, i32)> = order
+                .into_iter()
+                .filter(|&i| s.items[i].list == s.active)
+                .filter_map(|i| {
+                    let m = f.matches(&s.items[i])?;
+                    Some((s.items[i].clone(), m.positions, m.score))
+                })
+                .collect();
+            // While searching, the best matches come first.
+            if !f.query.trim().is_empty() {
+                items_snapshot.sort_by_key(|(_, _, score)| std::cmp::Reverse(*score));
+            }
+            let hidden_all = items_snapshot.is_empty() && s.active_items().next().is_some();
+            drop(f);
+            drop(s); // release borrow before building rows
+
+            let mut rows = Vec::with_capacity(items_snapshot.len());
+            for (item, matched, _) in items_snapshot {
+                let row = build_todo_row(&item, &matched, &row_ctx);
+                list_box.append(&row);
+                rows.push((item.id, row));
+            }
+
+            if let (Some(pos), Some(last)) = (focused_pos, rows.len().checked_sub(1)) {
+                let cursor = row_ctx.cursor.get();
+                let target = rows
+                    .iter()
+                    .find(|(id, _)| Some(*id) == cursor)
+                    .unwrap_or(&rows[pos.min(last)]);
+                if let Some(row) = target.1.first_child() {
+                    row.grab_focus();
+                }
+            }
+
+            if hidden_all {
+                let empty = Label::new(Some("No matching tasks"));
+                empty.add_css_class("zenith-todo-empty");
+                list_box.append(&empty);
+            }
+
+            // ── Rebuild archive / time / stats view ──────────────
+            let page = stack.upgrade().and_then(|st| st.visible_child_name());
+            match page.as_deref() {
+                Some("archive") => {
+                    if let Some(list) = archive_list.upgrade() {
+                        fill_archive_list(&list, &row_ctx);
+                    }
+                }
+                Some("time") => {
+                    if let Some(list) = time_list.upgrade() {
+                        fill_time_list(&list, &row_ctx);
+                    }
+                }
+                Some("stats") => {
+                    if let Some(list) = stats_list.upgrade() {
+                        fill_stats_page(&list, &row_ctx);
+                    }
+                }
+                _ => {}
+            }
+
+            reminders.rearm();
+            timer_tick.rearm();
+            if let Some(ref sync) = caldav {
+                sync.changed();
+            }
+        }) as Box<dyn Fn()>
+    };
+
+    *refresh.borrow_mut() = Some(build_refresh());
+
+    // Archive anything that expired while the bar was not running, then
+    // keep checking once a minute.
+    if cfg.archive_after_hours.is_some() || cfg.archive_at_midnight {
+        store.borrow_mut().auto_archive(cfg);
+        let store = Rc::clone(&store);
+        let refresh = Rc::clone(&refresh);
+        let cfg = cfg.clone();
+        glib::timeout_add_seconds_local(60, move || {
+            let changed = store.borrow_mut().auto_archive(&cfg);
+            if changed {
+                if let Some(ref f) = *refresh.borrow() {
+                    f();
+                }
+            }
+            glib::ControlFlow::Continue
+        });
+    }
+
+    // Taskwarrior lists arrive once `task export` has run in the background.
+    store.borrow().on_background_load({
+        let store = Rc::downgrade(&store);
+        let refresh = Rc::clone(&refresh);
+        move |list| {
+            let Some(store) = store.upgrade() else {
+                return;
+            };
+            store.borrow_mut().refill(list);
+            if let Some(ref f) = *refresh.borrow() {
+                f();
+            }
+        }
+    });
+
+    // Pick up changes made from the command line.
+    if let Some(app) = gio::Application::default() {
+        let action = gio::SimpleAction::new(RELOAD_ACTION, None);
+        let store = Rc::clone(&store);
+        let refresh = Rc::clone(&refresh);
+        action.connect_activate(move |_, _| {
+            store.borrow_mut().reload();
+            if let Some(ref f) = *refresh.borrow() {
+                f();
+            }
+        });
+        app.add_action(&action);
+    }
+
+    // Initial paint
+    if let Some(ref f) = *refresh.borrow() {
+        f();
+    }
+
+    // ── Filter changes ───────────────────────────────────────────────
+    scope_dd.connect_selected_notify({
+        let filter_state = Rc::clone(&filter_state);
+        let scope_options = Rc::clone(&scope_options);
+        let updating_scopes = Rc::clone(&updating_scopes);
+        let refresh = Rc::clone(&refresh);
+        move |dd| {
+            if updating_scopes.get() {
+                return;
+            }
+            let scope = scope_options
+                .borrow()
+                .get(dd.selected() as usize)
+                .cloned()
+                .unwrap_or_default();
+            filter_state.borrow_mut().scope = scope;
+            if let Some(ref f) = *refresh.borrow() {
+                f();
+            }
+        }
+    });
+
+    band_dd.connect_selected_notify({
+        let filter_state = Rc::clone(&filter_state);
+        let refresh = Rc::clone(&refresh);
+        move |dd| {
+            filter_state.borrow_mut().band = filter::PriorityBand::from_index(dd.selected());
+            if let Some(ref f) = *refresh.borrow() {
+                f();
+            }
+        }
+    });
+
+    status_dd.connect_selected_notify({
+        let filter_state = Rc::clone(&filter_state);
+        let refresh = Rc::clone(&refresh);
+        move |dd| {
+            filter_state.borrow_mut().status = filter::Status::from_index(dd.selected());
+            if let Some(ref f) = *refresh.borrow() {
+                f();
+            }
+        }
+    });
+
+    // Escape in the search field clears it first, then closes.
+    search.connect_stop_search({
+        let popover = popover.downgrade();
+        move |e| {
+            if !e.text().is_empty() {
+                e.set_text("");
+            } else if let Some(popover) = popover.upgrade() {
+                popover.popdown();
+            }
+        }
+    });
+
+    // Down or Enter in the search field moves on to the filtered rows.
+    let search_keys = EventControllerKey::new();
+    search_keys.set_propagation_phase(gtk4::PropagationPhase::Capture);
+    search_keys.connect_key_pressed({
+        let list_box = Rc::clone(&list_box_rc);
+        move |_, key, _, _| match key {
+            gdk4::Key::Down if focus_first_row(&list_box) => glib::Propagation::Stop,
+            _ => glib::Propagation::Proceed,
+        }
+    });
+    search.add_controller(search_keys);
+    search.connect_activate({
+        let list_box = Rc::clone(&list_box_rc);
+        move |_| {
+            focus_first_row(&list_box);
+        }
+    });
+
+    search.connect_search_changed({
+        let filter_state = Rc::clone(&filter_state);
+        let refresh = Rc::clone(&refresh);
+        move |e| {
+            filter_state.borrow_mut().query = e.text().to_string();
+            if let Some(ref f) = *refresh.borrow() {
+                f();
+            }
+        }
+    });
+
+    sort_dd.connect_selected_notify({
+        let ctx = Rc::clone(&row_ctx);
+        move |dd| {
+            ctx.sort.set(sort::from_index(dd.selected()));
+            ctx.refresh();
+        }
+    });
+
+    // ── Archive actions ──────────────────────────────────────────────
+    clear_btn.connect_clicked({
+        let store = Rc::clone(&store_rc);
+        let refresh = Rc::clone(&refresh);
+        let toast_ctx = Rc::clone(&row_ctx);
+        move |_| {
+            let mut s = store.borrow_mut();
+            let active = s.active;
+            let n = match s.archive_done(|t| t.list == active) {
+                Ok(0) => return,
+                Ok(n) => n,
+                Err(_) => {
+                    drop(s);
+                    toast_ctx.toast.notice("Could not write the archive");
+                    return;
+                }
+            };
+            drop(s);
+            if let Some(ref f) = *refresh.borrow() {
+                f();
+            }
+            let message = match n {
+                1 => "1 task archived".to_string(),
+                n => format!("{} tasks archived", n),
+            };
+            toast_ctx.toast.show(&message);
+        }
+    });
+
+    list_dd.connect_selected_notify({
+        let ctx = Rc::clone(&row_ctx);
+        move |dd| {
+            ctx.store.borrow_mut().active = dd.selected() as usize;
+            ctx.cursor.set(None);
+            ctx.refresh();
+        }
+    });
+
+    let pages_btn = build_pages_menu(&stack, &filter_bar, &search, &refresh);
+    header.insert_child_after(&pages_btn, Some(&clear_btn));
+
+    // ── Popover toggle ───────────────────────────────────────────────
+    bar_btn.connect_clicked({
+        let popover = popover.clone();
+        move |_| {
+            if popover.is_visible() {
+                popover.popdown();
+            } else {
+                popover.popup();
+            }
+        }
+    });
+
+    // ── Add task via button or Enter ─────────────────────────────────
+    let add_task = {
+        let store = Rc::clone(&store_rc);
+        let entry = entry.clone();
+        let refresh = Rc::clone(&refresh);
+        move || {
+            let text = entry.text().trim().to_string();
+            if text.is_empty() {
+                return;
+            }
+
+            // Parse optional priority prefix ("3:Deploy server" → priority=3)
+            // and natural-language dates ("tomorrow 9am", "every monday").
+            let mut s = store.borrow_mut();
+            s.checkpoint();
+            s.add(&text, Local::now().naive_local());
+            s.save();
+            drop(s);
+            entry.set_text("");
+
+            if let Some(ref f) = *refresh.borrow() {
+                f();
+            }
+        }
+    };
+
+    add_btn.connect_clicked({
+        let add_task = add_task.clone();
+        move |_| add_task()
+    });
+
+    entry.connect_activate(move |_| add_task());
+
+    // ── Preview chip follows the entry text ──────────────────────────
+    entry.connect_changed(move |e| {
+        let now = Local::now().naive_local();
+        let summary = parse::parse_task(&e.text(), now).summary(now.date());
+        match summary {
+            Some(text) => {
+                preview.set_label(&text);
+                preview.set_visible(true);
+            }
+            None => preview.set_visible(false),
+        }
+    });
+
+    let tasks = Tasks {
+        store: store_rc,
+        refresh,
+    };
+    (container, tasks)
+}
+
+/// Bar text while a timer runs: the task and the time on the clock.
+/// Ticks the bar once a second while a task's timer runs. The source only
+/// exists meanwhile: [`TimerTick::rearm`] starts it after a refresh, and it
+/// ends itself once no timer is running.
+struct TimerTick {
+    store: Rc<RefCell<TodoStore>>,
+    bar_btn: glib::WeakRef<Button>,
+    source: RefCell<Option<glib::SourceId>>,
+}
+
+impl TimerTick {
+    fn rearm(self: &Rc<Self>) {
+        if self.source.borrow().is_some() || self.store.borrow().running_timer().is_none() {
+            return;
+        }
+        let this = Rc::downgrade(self);
+        let source = glib::timeout_add_seconds_local(1, move || {
+            let Some(this) = this.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            let label = timer_label(&this.store.borrow(), Local::now().naive_local());
+            match (this.bar_btn.upgrade(), label) {
+                (Some(btn), Some(label)) => {
+                    btn.set_label(&label);
+                    glib::ControlFlow::Continue
+                }
+                _ => {
+                    // Ending by itself; the id must not be removed again.
+                    this.source.take();
+                    glib::ControlFlow::Break
+                }
+            }
+        });
+        *self.source.borrow_mut() = Some(source);
+    }
+}
+
+fn timer_label(store: &TodoStore, now: NaiveDateTime) -> Option<String> {
+    let (item, started) = store.running_timer()?;
+    let text: String = item.text.chars().take(24).collect();
+    Some(format!(
+        "⏱ {} {}",
+        text,
+        timetrack::format_clock(now - started)
+    ))
+}
+
+/// Build a single todo row widget, plus its subtask section when expanded.
+/// `matched` holds the char positions in the task text hit by the search.
+fn build_todo_row(item: &TodoItem, matched: &[usize], ctx: &Rc<RowContext>) -> GtkBox {
+    let wrapper = GtkBox::new(Orientation::Vertical, 0);
+
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+    row.add_css_class("zenith-todo-row");
+    if item.done {
+        row.add_css_class("zenith-todo-row-done");
+    }
+    wrapper.append(&row);
+
+    // Priority accent bar (thin colored stripe on the left)
+    let accent = GtkBox::new(Orientation::Vertical, 0);
+    accent.set_width_request(3);
+    accent.set_vexpand(true);
+    accent.add_css_class("zenith-todo-accent");
+    match item.priority {
+        1..=3 => accent.add_css_class("zenith-todo-prio-high"),
+        4..=6 => accent.add_css_class("zenith-todo-prio-mid"),
+        7..=9 => accent.add_css_class("zenith-todo-prio-low"),
+        _ => accent.add_css_class("zenith-todo-prio-none"),
+    }
+    row.append(&accent);
+
+    // Expander for the subtask section
+    let expanded = ctx.expanded.borrow().contains(&item.id);
+    let expander = Button::with_label(if expanded { "▾" } else { "▸" });
+    expander.add_css_class("zenith-todo-expander");
+    expander.set_tooltip_text(Some("Subtasks"));
+    let ctx_c = Rc::clone(ctx);
+    let id = item.id;
+    expander.connect_clicked(move |_| {
+        {
+            let mut set = ctx_c.expanded.borrow_mut();
+            if !set.remove(&id) {
+                set.insert(id);
+            }
+        }
+        ctx_c.refresh();
+    });
+    row.append(&expander);
+
+    // Checkbox
+    let check = CheckButton::new();
+    check.set_active(item.done);
+    check.add_css_class("zenith-todo-check");
+    row.append(&check);
+
+    // Task text
+    let label = Label::new(Some(&item.text));
+    if !matched.is_empty() {
+        label.set_markup(&highlight_markup(&item.text, matched));
+    }
+    label.set_hexpand(true);
+    label.set_halign(Align::Start);
+    label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+    label.add_css_class("zenith-todo-text");
+    if item.done {
+        label.add_css_class("zenith-todo-text-done");
+    }
+    label.set_tooltip_text(Some("Double-click to edit"));
+    row.append(&label);
+
+    // Double-click swaps the label for an entry holding the task in entry
+    // syntax; Enter re-parses it, Escape cancels.
+    let dbl_click = GestureClick::new();
+    let ctx_c = Rc::clone(ctx);
+    let row_w = row.downgrade();
+    let label_w = label.downgrade();
+    let edit_text = parse::format_task(item);
+    let id = item.id;
+    dbl_click.connect_pressed(move |_, n_press, _, _| {
+        if n_press != 2 {
+            return;
+        }
+        let (Some(row), Some(label)) = (row_w.upgrade(), label_w.upgrade()) else {
+            return;
+        };
+        start_inline_edit(id, &row, &label, &edit_text, &ctx_c);
+    });
+    label.add_controller(dbl_click);
+
+    // Due / recurrence chip
+    if let Some(due) = item.due {
+        let now = Local::now().naive_local();
+        let text = match item.recurrence {
+            Some(_) => format!("↻ {}", due.describe(now.date())),
+            None => due.describe(now.date()),
+        };
+        let chip = Label::new(Some(&text));
+        chip.add_css_class("zenith-todo-due");
+        if !item.done && due.deadline() < now {
+            chip.add_css_class("zenith-todo-due-overdue");
+        }
+        if let Some(rec) = item.recurrence {
+            let tooltip = match item.completions.len() {
+                0 => rec.to_string(),
+                n => format!("{} · done {}×", rec, n),
+            };
+            chip.set_tooltip_text(Some(&tooltip));
+        }
+        row.append(&chip);
+    }
+
+    // Project / tag chips
+    let chips = item
+        .project
+        .iter()
+        .map(|p| (format!("+{}", p), "zenith-todo-project"))
+        .chain(
+            item.tags
+                .iter()
+                .map(|t| (format!("#{}", t), "zenith-todo-tag")),
+        );
+    for (text, class) in chips {
+        let chip = Label::new(Some(&text));
+        chip.add_css_class("zenith-todo-chip");
+        chip.add_css_class(class);
+        row.append(&chip);
+    }
+
+    // Reminder bell for explicit `remind:` times
+    if let (Some(remind), false) = (item.remind, item.done) {
+        let bell = Label::new(Some("⏰"));
+        bell.add_css_class("zenith-todo-remind");
+        bell.set_tooltip_text(Some(&format!(
+            "Reminder {}",
+            remind.format("%a %d %b %H:%M")
+        )));
+        row.append(&bell);
+    }
+
+    // Finished pomodoros
+    if item.pomodoros > 0 {
+        let count = Label::new(Some(&format!("🍅{}", item.pomodoros)));
+        count.add_css_class("zenith-todo-pomodoros");
+        count.set_tooltip_text(Some(&format!("{} pomodoro(s) finished", item.pomodoros)));
+        row.append(&count);
+    }
+
+    // Subtask progress badge
+    let (sub_done, sub_total) = item.subtask_progress();
+    if sub_total > 0 {
+        let badge = Label::new(Some(&format!("{}/{}", sub_done, sub_total)));
+        badge.add_css_class("zenith-todo-subtask-badge");
+        if sub_done == sub_total {
+            badge.add_css_class("zenith-todo-subtask-badge-done");
+        }
+        row.append(&badge);
+    }
+
+    // Priority badge (if set)
+    if item.priority > 0 {
+        let badge = Label::new(Some(&format!("P{}", item.priority)));
+        badge.add_css_class("zenith-todo-badge");
+        match item.priority {
+            1..=3 => badge.add_css_class("zenith-todo-badge-high"),
+            4..=6 => badge.add_css_class("zenith-todo-badge-mid"),
+            _ => badge.add_css_class("zenith-todo-badge-low"),
+        }
+        row.append(&badge);
+    }
+
+    // Start/stop time tracking
+    let now = Local::now().naive_local();
+    let timing = item.timer_started().is_some();
+    if !item.done || !item.time_entries.is_empty() {
+        let timer_btn = Button::with_label(if timing { "■" } else { "▶" });
+        timer_btn.add_css_class("zenith-todo-timer-btn");
+        if timing {
+            timer_btn.add_css_class("zenith-todo-timer-btn-active");
+        }
+        timer_btn.set_sensitive(!item.done);
+        let tracked = item.tracked(now);
+        let tooltip = match (timing, tracked.num_minutes()) {
+            (true, _) => format!(
+                "Stop timer · {} tracked",
+                timetrack::format_duration(tracked)
+            ),
+            (false, 0) => "Start timer".to_string(),
+            (false, _) => format!(
+                "Start timer · {} tracked",
+                timetrack::format_duration(tracked)
+            ),
+        };
+        timer_btn.set_tooltip_text(Some(&tooltip));
+        let ctx_c = Rc::clone(ctx);
+        let id = item.id;
+        timer_btn.connect_clicked(move |_| {
+            let now = Local::now().naive_local();
+            let mut s = ctx_c.store.borrow_mut();
+            s.checkpoint();
+            let stopped = s.find_mut(id).is_some_and(|t| t.stop_timer(now));
+            if !stopped {
+                s.start_timer(id, now);
+            }
+            s.save();
+            drop(s);
+            ctx_c.refresh();
+        });
+        row.append(&timer_btn);
+    }
+
+    // Pin as the bar's focus
+    if !item.done {
+        let focus_btn = Button::with_label(if item.focus { "★" } else { "☆" });
+        focus_btn.add_css_class("zenith-todo-focus-btn");
+        if item.focus {
+            focus_btn.add_css_class("zenith-todo-focus-btn-active");
+        }
+        focus_btn.set_tooltip_text(Some(if item.focus {
+            "Unpin from the bar"
+        } else {
+            "Show this task on the bar"
+        }));
+        let ctx_c = Rc::clone(ctx);
+        let id = item.id;
+        focus_btn.connect_clicked(move |_| {
+            let mut s = ctx_c.store.borrow_mut();
+            s.checkpoint();
+            s.toggle_focus(id);
+            s.save();
+            drop(s);
+            ctx_c.refresh();
+        });
+        row.append(&focus_btn);
+    }
+
+    // Reordering by hand only makes sense in manual order over the full list.
+    let manual = ctx.can_reorder();
+
+    // Move up button
+    let store = ctx.store.borrow();
+    let id = item.id;
+    let has_neighbour = |up| store.position(id).and_then(|i| store.neighbour(i, up));
+    if manual && has_neighbour(true).is_some() {
+        let up_btn = Button::with_label("▲");
+        up_btn.add_css_class("zenith-todo-move-btn");
+        let ctx_c = Rc::clone(ctx);
+        up_btn.connect_clicked(move |_| ctx_c.swap(id, true));
+        row.append(&up_btn);
+    }
+
+    // Move down button
+    if manual && has_neighbour(false).is_some() {
+        let down_btn = Button::with_label("▼");
+        down_btn.add_css_class("zenith-todo-move-btn");
+        let ctx_c = Rc::clone(ctx);
+        down_btn.connect_clicked(move |_| ctx_c.swap(id, false));
+        row.append(&down_btn);
+    }
+
+    // Move to another list
+    let other_lists: Vec<(usize, String)> = store
+        .list_names()
+        .enumerate()
+        .filter(|(list, _)| *list != item.list)
+        .map(|(list, name)| (list, name.to_string()))
+        .collect();
+    drop(store);
+    if !other_lists.is_empty() {
+        row.append(&build_move_list_button(item.id, other_lists, ctx));
+    }
+
+    // Delete button
+    let del_btn = Button::with_label("✕");
+    del_btn.add_css_class("zenith-todo-del-btn");
+    let ctx_c = Rc::clone(ctx);
+    del_btn.connect_clicked(move |_| {
+        let mut s = ctx_c.store.borrow_mut();
+        let removed = s.find(id).is_some();
+        if removed {
+            s.checkpoint();
+            s.remove(id);
+            s.save();
+        }
+        drop(s);
+        ctx_c.refresh();
+        if removed {
+            ctx_c.toast.show("Task deleted");
+        }
+    });
+    row.append(&del_btn);
+
+    // Checkbox toggle
+    let ctx_c = Rc::clone(ctx);
+    check.connect_toggled(move |cb| {
+        let mut s = ctx_c.store.borrow_mut();
+        if s.find(id).is_some() {
+            s.checkpoint();
+            if let Some(item) = s.find_mut(id) {
+                if cb.is_active() {
+                    item.complete(Local::now().naive_local());
+                } else {
+                    item.reopen();
+                }
+            }
+            s.save();
+        }
+        drop(s);
+        ctx_c.refresh();
+    });
+
+    if manual {
+        attach_drag_and_drop(id, &row, ctx);
+    }
+
+    attach_row_keys(item, &row, &label, &check, &del_btn, &expander, ctx);
+
+    if expanded {
+        wrapper.append(&build_subtask_section(item, ctx));
+    }
+
+    wrapper
+}
+
+/// Button opening a menu of the other lists to move task `id` to.
+fn build_move_list_button(
+    id: u64,
+    lists: Vec<(usize, String)>,
+    ctx: &Rc<RowContext>,
+) -> MenuButton {
+    let btn = MenuButton::new();
+    btn.set_label("⇄");
+    btn.add_css_class("zenith-todo-list-move-btn");
+    btn.set_tooltip_text(Some("Move to another list"));
+
+    let popover = Popover::new();
+    popover.add_css_class("zenith-todo-popup");
+    let menu = GtkBox::new(Orientation::Vertical, 4);
+    menu.add_css_class("zenith-todo-exchange-menu");
+    for (list, name) in lists {
+        let entry = Button::with_label(&name);
+        entry.add_css_class("zenith-todo-header-btn");
+        let ctx_c = Rc::clone(ctx);
+        let popover_c = popover.clone();
+        entry.connect_clicked(move |_| {
+            popover_c.popdown();
+            let mut s = ctx_c.store.borrow_mut();
+            if !s.move_to_list(id, list) {
+                return;
+            }
+            s.save();
+            drop(s);
+            ctx_c.refresh();
+            ctx_c.toast.show(&format!("Moved to {}", name));
+        });
+        menu.append(&entry);
+    }
+    popover.set_child(Some(&menu));
+    btn.set_popover(Some(&popover));
+    btn
+}
+
+/// Keyboard handling for a task row. Rows take focus themselves while their
+/// buttons do not, so Tab and the arrow keys move from task to task:
+///
+/// - Up/Down: previous/next task; Alt+Up/Down moves the task (manual order,
+///   unfiltered)
+/// - Space: toggle done; Delete: remove; `e` or Enter: edit
+/// - Right/Left: expand/collapse the subtasks
+#[allow(clippy::too_many_arguments)]
+fn attach_row_keys(
+    item: &TodoItem,
+    row: &GtkBox,
+    label: &Label,
+    check: &CheckButton,
+    del_btn: &Button,
+    expander: &Button,
+    ctx: &Rc<RowContext>,
+) {
+    row.set_focusable(true);
+    let mut child = row.first_child();
+    while let Some(c) = child {
+        c.set_focusable(false);
+        child = c.next_sibling();
+    }
+
+    let focus = gtk4::EventControllerFocus::new();
+    let ctx_c = Rc::clone(ctx);
+    let id = item.id;
+    focus.connect_enter(move |_| ctx_c.cursor.set(Some(id)));
+    row.add_controller(focus);
+
+    let keys = EventControllerKey::new();
+    let ctx_c = Rc::clone(ctx);
+    let row_w = row.downgrade();
+    let label_w = label.downgrade();
+    let check = check.downgrade();
+    let del_btn = del_btn.downgrade();
+    let expander = expander.downgrade();
+    let expanded = ctx.expanded.borrow().contains(&item.id);
+    let edit_text = parse::format_task(item);
+    keys.connect_key_pressed(move |_, key, _, state| {
+        // Leave keys from the inline edit entry alone.
+        if !row_w.upgrade().is_some_and(|r| r.has_focus()) {
+            return glib::Propagation::Proceed;
+        }
+        let alt = state.contains(gdk4::ModifierType::ALT_MASK);
+        match key {
+            gdk4::Key::Up | gdk4::Key::Down if alt => {
+                if ctx_c.can_reorder() {
+                    ctx_c.swap(id, key == gdk4::Key::Up);
+                }
+            }
+            gdk4::Key::Up | gdk4::Key::Down => {
+                let down = key == gdk4::Key::Down;
+                if let Some(row) = row_w.upgrade() {
+                    if !focus_neighbour(&row, down) && !down {
+                        if let Some(search) = ctx_c.search.upgrade() {
+                            search.grab_focus();
+                        }
+                    }
+                }
+            }
+            gdk4::Key::space => {
+                if let Some(check) = check.upgrade() {
+                    check.set_active(!check.is_active());
+                }
+            }
+            gdk4::Key::Delete | gdk4::Key::KP_Delete => {
+                if let Some(btn) = del_btn.upgrade() {
+                    btn.emit_clicked();
+                }
+            }
+            gdk4::Key::e | gdk4::Key::Return | gdk4::Key::KP_Enter => {
+                if let (Some(row), Some(label)) = (row_w.upgrade(), label_w.upgrade()) {
+                    start_inline_edit(id, &row, &label, &edit_text, &ctx_c);
+                }
+            }
+            gdk4::Key::Right | gdk4::Key::Left => {
+                if expanded != (key == gdk4::Key::Right) {
+                    if let Some(btn) = expander.upgrade() {
+                        btn.emit_clicked();
+                    }
+                }
+            }
+            _ => return glib::Propagation::Proceed,
+        }
+        glib::Propagation::Stop
+    });
+    row.add_controller(keys);
+}
+
+/// Move keyboard focus to the task row above or below `row`. Returns false
+/// when there is no such row.
+fn focus_neighbour(row: &GtkBox, down: bool) -> bool {
+    // Rows sit inside a wrapper that also holds their subtask section.
+    let Some(wrapper) = row.parent() else {
+        return false;
+    };
+    let next = if down {
+        wrapper.next_sibling()
+    } else {
+        wrapper.prev_sibling()
+    };
+    match next.and_then(|w| w.first_child()) {
+        Some(next_row) => next_row.grab_focus(),
+        None => false,
+    }
+}
+
+/// Move keyboard focus to the first task row in `list`.
+fn focus_first_row(list: &GtkBox) -> bool {
+    list.first_child()
+        .and_then(|w| w.first_child())
+        .is_some_and(|row| row.grab_focus())
+}
+
+/// Pango markup for `text` with the chars at `positions` emphasised.
+fn highlight_markup(text: &str, positions: &[usize]) -> String {
+    let mut out = String::new();
+    let mut run = String::new();
+    let mut in_match = false;
+    let flush = |out: &mut String, run: &mut String, hit: bool| {
+        if run.is_empty() {
+            return;
+        }
+        let escaped = glib::markup_escape_text(run);
+        if hit {
+            out.push_str(&format!(
+                "<span weight=\"bold\" underline=\"single\">{}</span>",
+                escaped
+            ));
+        } else {
+            out.push_str(&escaped);
+        }
+        run.clear();
+    };
+    for (i, c) in text.chars().enumerate() {
+        let hit = positions.contains(&i);
+        if hit != in_match {
+            flush(&mut out, &mut run, in_match);
+            in_match = hit;
+        }
+        run.push(c);
+    }
+    flush(&mut out, &mut run, in_match);
+    out
+}
+
+/// Replace `label` with an entry for editing the task in place.
+fn start_inline_edit(id: u64, row: &GtkBox, label: &Label, text: &str, ctx: &Rc<RowContext>) {
+    let entry = Entry::new();
+    entry.set_text(text);
+    entry.set_hexpand(true);
+    entry.add_css_class("zenith-todo-edit-entry");
+    row.insert_child_after(&entry, Some(label));
+    label.set_visible(false);
+    entry.grab_focus();
+
+    let ctx_c = Rc::clone(ctx);
+    entry.connect_activate(move |e| {
+        let text = e.text().trim().to_string();
+        if !text.is_empty() {
+            let parsed = parse::parse_task(&text, Local::now().naive_local());
+            let mut s = ctx_c.store.borrow_mut();
+            if s.find(id).is_some() {
+                s.checkpoint();
+                if let Some(item) = s.find_mut(id) {
+                    item.apply(parsed);
+                }
+                s.save();
+            }
+        }
+        ctx_c.refresh();
+    });
+
+    let keys = EventControllerKey::new();
+    let ctx_c = Rc::clone(ctx);
+    keys.connect_key_pressed(move |_, key, _, _| {
+        if key == gdk4::Key::Escape {
+            ctx_c.refresh();
+            glib::Propagation::Stop
+        } else {
+            glib::Propagation::Proceed
+        }
+    });
+    entry.add_controller(keys);
+}
+
+/// Make `row` draggable and accept other rows dropped onto it. Dropping on
+/// the upper half inserts before this row, the lower half after it.
+fn attach_drag_and_drop(id: u64, row: &GtkBox, ctx: &Rc<RowContext>) {
+    let source = DragSource::new();
+    source.set_actions(gdk4::DragAction::MOVE);
+    source.connect_prepare(move |_, _, _| Some(gdk4::ContentProvider::for_value(&id.to_value())));
+    let row_w = row.downgrade();
+    source.connect_drag_begin(move |src, _| {
+        if let Some(row) = row_w.upgrade() {
+            let icon = WidgetPaintable::new(Some(&row));
+            src.set_icon(Some(&icon), 0, 0);
+        }
+    });
+    row.add_controller(source);
+
+    let target = DropTarget::new(u64::static_type(), gdk4::DragAction::MOVE);
+    let ctx_c = Rc::clone(ctx);
+    let row_w = row.downgrade();
+    target.connect_drop(move |_, value, _, y| {
+        let Ok(dragged) = value.get::<u64>() else {
+            return false;
+        };
+        let lower_half = row_w.upgrade().is_some_and(|r| y > r.height() as f64 / 2.0);
+
+        // Both tasks are looked up now; the list may have changed since the
+        // rows were built.
+        let mut s = ctx_c.store.borrow_mut();
+        let (Some(from), Some(at)) = (s.position(dragged), s.position(id)) else {
+            return false;
+        };
+        let mut to = if lower_half { at + 1 } else { at };
+        if from < to {
+            to -= 1;
+        }
+        if from != to {
+            s.checkpoint();
+            s.move_item(from, to);
+            s.save();
+        }
+        drop(s);
+
+        // Rebuild once the drop has finished rather than while GTK is
+        // still dispatching it to this row.
+        let ctx_c = Rc::clone(&ctx_c);
+        glib::idle_add_local_once(move || ctx_c.refresh());
+        true
+    });
+    row.add_controller(target);
+}
+
+/// Indented checklist under an expanded row, ending in an "add subtask" entry.
+fn build_subtask_section(item: &TodoItem, ctx: &Rc<RowContext>) -> GtkBox {
+    let id = item.id;
+    let section = GtkBox::new(Orientation::Vertical, 2);
+    section.add_css_class("zenith-todo-subtasks");
+
+    for (sub_idx, sub) in item.subtasks.iter().enumerate() {
+        let sub_row = GtkBox::new(Orientation::Horizontal, 6);
+        sub_row.add_css_class("zenith-todo-subtask-row");
+
+        let check = CheckButton::new();
+        check.set_active(sub.done);
+        check.add_css_class("zenith-todo-check");
+        sub_row.append(&check);
+
+        let label = Label::new(Some(&sub.text));
+        label.set_hexpand(true);
+        label.set_halign(Align::Start);
+        label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+        label.add_css_class("zenith-todo-subtask-text");
+        if sub.done {
+            label.add_css_class("zenith-todo-text-done");
+        }
+        sub_row.append(&label);
+
+        let del_btn = Button::with_label("✕");
+        del_btn.add_css_class("zenith-todo-del-btn");
+        let ctx_c = Rc::clone(ctx);
+        del_btn.connect_clicked(move |_| {
+            let mut s = ctx_c.store.borrow_mut();
+            if s.find(id).is_some_and(|t| sub_idx < t.subtasks.len()) {
+                s.checkpoint();
+                if let Some(item) = s.find_mut(id) {
+                    item.subtasks.remove(sub_idx);
+                }
+                s.save();
+            }
+            drop(s);
+            ctx_c.refresh();
+        });
+        sub_row.append(&del_btn);
+
+        let ctx_c = Rc::clone(ctx);
+        check.connect_toggled(move |cb| {
+            let mut s = ctx_c.store.borrow_mut();
+            if s.find(id).is_some() {
+                s.checkpoint();
+                if let Some(item) = s.find_mut(id) {
+                    item.set_subtask_done(
+                        sub_idx,
+                        cb.is_active(),
+                        ctx_c.cfg.auto_complete_parent,
+                        Local::now().naive_local(),
+                    );
+                }
+                s.save();
+            }
+            drop(s);
+            ctx_c.refresh();
+        });
+
+        section.append(&sub_row);
+    }
+
+    let entry = Entry::new();
+    entry.set_placeholder_text(Some("Add subtask…"));
+    entry.add_css_class("zenith-todo-subtask-entry");
+    let ctx_c = Rc::clone(ctx);
+    entry.connect_activate(move |e| {
+        let text = e.text().trim().to_string();
+        if text.is_empty() {
+            return;
+        }
+        let mut s = ctx_c.store.borrow_mut();
+        if s.find(id).is_some() {
+            s.checkpoint();
+            if let Some(item) = s.find_mut(id) {
+                item.subtasks.push(Subtask { text, done: false });
+                // A new open subtask reopens an auto-completed parent.
+                if ctx_c.cfg.auto_complete_parent && item.done {
+                    item.reopen();
+                }
+            }
+            s.save();
+        }
+        drop(s);
+        ctx_c.refresh();
+    });
+    section.append(&entry);
+
+    section
+}
+
+/// Header menu swapping the list for the Archive, Time or Stats page and
+/// back. A menu rather than a button each keeps the header within the
+/// popover's width.
+fn build_pages_menu(
+    stack: &Stack,
+    filter_bar: &GtkBox,
+    search: &SearchEntry,
+    refresh: &RefreshCallback,
+) -> MenuButton {
+    let menu_btn = MenuButton::new();
+    menu_btn.set_label("☰");
+    menu_btn.add_css_class("zenith-todo-header-btn");
+    menu_btn.set_tooltip_text(Some("Archive, time and stats"));
+
+    let popover = Popover::new();
+    popover.add_css_class("zenith-todo-popup");
+    let menu = GtkBox::new(Orientation::Vertical, 4);
+    menu.add_css_class("zenith-todo-exchange-menu");
+    popover.set_child(Some(&menu));
+    menu_btn.set_popover(Some(&popover));
+
+    for (label, page, tooltip) in [
+        ("Tasks", "tasks", None),
+        (
+            "Archive",
+            "archive",
+            Some("Completed tasks moved out of the list"),
+        ),
+        ("Time", "time", Some("Time tracked today and this week")),
+        (
+            "Stats",
+            "stats",
+            Some("Completed tasks, streak and overdue count"),
+        ),
+    ] {
+        let btn = Button::with_label(label);
+        btn.add_css_class("zenith-todo-header-btn");
+        btn.set_tooltip_text(tooltip);
+        if let Some(child) = btn.child() {
+            child.set_halign(Align::Start);
+        }
+        menu.append(&btn);
+
+        let stack = stack.clone();
+        let filter_bar = filter_bar.clone();
+        let search = search.clone();
+        let refresh = Rc::clone(refresh);
+        let popover = popover.clone();
+        btn.connect_clicked(move |_| {
+            popover.popdown();
+            stack.set_visible_child_name(page);
+            // Search and filters only apply to the list.
+            filter_bar.set_visible(page == "tasks");
+            search.set_visible(page == "tasks");
+            if let Some(ref f) = *refresh.borrow() {
+                f();
+            }
+        });
+    }
+    menu_btn
+}
+
+/// Header menu for copying the list out as markdown or JSON and pulling
+/// tasks in from the clipboard or a file.
+fn build_exchange_menu(ctx: &Rc<RowContext>) -> MenuButton {
+    use exchange::Format;
+
+    let menu_btn = MenuButton::new();
+    menu_btn.set_label("⇅");
+    menu_btn.add_css_class("zenith-todo-header-btn");
+    menu_btn.set_tooltip_text(Some("Import / export"));
+
+    let popover = Popover::new();
+    popover.add_css_class("zenith-todo-popup");
+    let menu = GtkBox::new(Orientation::Vertical, 4);
+    menu.add_css_class("zenith-todo-exchange-menu");
+    popover.set_child(Some(&menu));
+    menu_btn.set_popover(Some(&popover));
+
+    let entry = |label: &str| {
+        let btn = Button::with_label(label);
+        btn.add_css_class("zenith-todo-header-btn");
+        if let Some(child) = btn.child() {
+            child.set_halign(Align::Start);
+        }
+        menu.append(&btn);
+        btn
+    };
+
+    for (label, format) in [
+        ("Copy as Markdown", Format::Markdown),
+        ("Copy as JSON", Format::Json),
+    ] {
+        let ctx_c = Rc::clone(ctx);
+        let popover = popover.clone();
+        entry(label).connect_clicked(move |btn| {
+            let items: Vec<TodoItem> = ctx_c.store.borrow().active_items().cloned().collect();
+            match exchange::export(&items, format) {
+                Ok(text) => {
+                    btn.clipboard().set_text(&text);
+                    ctx_c.toast.notice("Copied task list");
+                }
+                Err(e) => log::error!("Failed to export tasks: {e:#}"),
+            }
+            popover.popdown();
+        });
+    }
+
+    let ctx_c = Rc::clone(ctx);
+    let popover_c = popover.clone();
+    entry("Save to Documents").connect_clicked(move |_| {
+        popover_c.popdown();
+        let dir = dirs::document_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_default();
+        let path = dir.join(exchange::export_name(
+            Local::now().date_naive(),
+            Format::Markdown,
+        ));
+        let items: Vec<TodoItem> = ctx_c.store.borrow().active_items().cloned().collect();
+        let text = exchange::to_markdown(&items);
+        match std::fs::write(&path, text) {
+            Ok(()) => ctx_c.toast.notice(&format!("Saved {}", path.display())),
+            Err(e) => {
+                log::error!("Failed to write {}: {e}", path.display());
+                ctx_c.toast.notice("Export failed");
+            }
+        }
+    });
+
+    menu.append(&Separator::new(Orientation::Horizontal));
+
+    let ctx_c = Rc::clone(ctx);
+    let popover_c = popover.clone();
+    entry("Paste from clipboard").connect_clicked(move |btn| {
+        popover_c.popdown();
+        let clipboard = btn.clipboard();
+        let ctx = Rc::clone(&ctx_c);
+        glib::spawn_future_local(async move {
+            match clipboard.read_text_future().await {
+                Ok(Some(text)) => import_tasks(&ctx, &text, None),
+                Ok(None) => ctx.toast.notice("Clipboard has no text"),
+                Err(e) => {
+                    log::warn!("Failed to read the clipboard: {e}");
+                    ctx.toast.notice("Clipboard has no text");
+                }
+            }
+        });
+    });
+
+    let ctx_c = Rc::clone(ctx);
+    let popover_c = popover.clone();
+    entry("Import file…").connect_clicked(move |btn| {
+        popover_c.popdown();
+        let window = btn.root().and_downcast::<gtk4::Window>();
+        let ctx = Rc::clone(&ctx_c);
+        glib::spawn_future_local(async move {
+            let dialog = gtk4::FileDialog::new();
+            dialog.set_title("Import tasks");
+            // Dismissing the dialog also ends up here.
+            let Some(path) = dialog
+                .open_future(window.as_ref())
+                .await
+                .ok()
+                .and_then(|file| file.path())
+            else {
+                return;
+            };
+            match std::fs::read_to_string(&path) {
+                Ok(text) => import_tasks(&ctx, &text, Format::from_path(&path)),
+                Err(e) => {
+                    log::error!("Failed to read {}: {e}", path.display());
+                    ctx.toast.notice("Import failed");
+                }
+            }
+        });
+    });
+
+    menu_btn
+}
+
+/// Append the tasks in `text` to the list as one undoable step. Without a
+/// `format` it is guessed from the contents.
+fn import_tasks(ctx: &Rc<RowContext>, text: &str, format: Option<exchange::Format>) {
+    let format = format.unwrap_or_else(|| exchange::Format::sniff(text));
+    let items = match exchange::import(text, format, Local::now().naive_local()) {
+        Ok(items) => items,
+        Err(e) => {
+            log::warn!("Nothing imported: {e:#}");
+            ctx.toast.notice("No tasks found to import");
+            return;
+        }
+    };
+    let mut s = ctx.store.borrow_mut();
+    s.checkpoint();
+    let count = s.append(items);
+    s.save();
+    drop(s);
+    ctx.refresh();
+    ctx.toast.show(&match count {
+        1 => "1 task imported".to_string(),
+        n => format!("{} tasks imported", n),
+    });
+}
+
+/// Today's and this week's tracked time per task, archived tasks included,
+/// followed by the CSV export button.
+fn fill_time_list(list: &GtkBox, ctx: &Rc<RowContext>) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+
+    let now = Local::now().naive_local();
+    let archive = archive::Archive::load_or_empty();
+    let store = ctx.store.borrow();
+    let all = || store.items.iter().chain(archive.tasks());
+
+    for (period, title) in [
+        (timetrack::Period::Today, "Today"),
+        (timetrack::Period::ThisWeek, "This week"),
+    ] {
+        let totals = timetrack::summary(all(), period, now);
+        let sum = totals.iter().fold(Duration::zero(), |acc, (_, d)| acc + *d);
+
+        let heading = GtkBox::new(Orientation::Horizontal, 8);
+        heading.add_css_class("zenith-todo-time-heading");
+        let name = Label::new(Some(title));
+        name.set_hexpand(true);
+        name.set_halign(Align::Start);
+        heading.append(&name);
+        heading.append(&Label::new(Some(&timetrack::format_duration(sum))));
+        list.append(&heading);
+
+        if totals.is_empty() {
+            let empty = Label::new(Some("Nothing tracked"));
+            empty.add_css_class("zenith-todo-empty");
+            list.append(&empty);
+        }
+        for (item, spent) in totals {
+            let row = GtkBox::new(Orientation::Horizontal, 8);
+            row.add_css_class("zenith-todo-row");
+            row.add_css_class("zenith-todo-time-row");
+
+            let label = Label::new(Some(&item.text));
+            label.set_hexpand(true);
+            label.set_halign(Align::Start);
+            label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+            label.add_css_class("zenith-todo-text");
+            row.append(&label);
+
+            if item.timer_started().is_some() {
+                let live = Label::new(Some("⏱"));
+                live.set_tooltip_text(Some("Timer running"));
+                row.append(&live);
+            }
+
+            let amount = Label::new(Some(&timetrack::format_duration(spent)));
+            amount.add_css_class("zenith-todo-time-amount");
+            row.append(&amount);
+            list.append(&row);
+        }
+    }
+
+    let csv = timetrack::to_csv(all());
+    drop(store);
+
+    let export_btn = Button::with_label("Export CSV");
+    export_btn.add_css_class("zenith-todo-restore-btn");
+    export_btn.add_css_class("zenith-todo-export-btn");
+    export_btn.set_halign(Align::End);
+    export_btn.set_tooltip_text(Some("Save every finished time entry to Documents"));
+    let ctx_c = Rc::clone(ctx);
+    export_btn.connect_clicked(move |_| {
+        let dir = dirs::document_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_default();
+        let path = dir.join(timetrack::export_name(Local::now().date_naive()));
+        match std::fs::write(&path, &csv) {
+            Ok(()) => ctx_c.toast.notice(&format!("Saved {}", path.display())),
+            Err(e) => {
+                log::error!("Failed to write {}: {e}", path.display());
+                ctx_c.toast.notice("Export failed");
+            }
+        }
+    });
+    list.append(&export_btn);
+}
+
+/// Heatmap cell size and the gap between cells, in pixels.
+const HEAT_CELL: f64 = 12.0;
+const HEAT_GAP: f64 = 3.0;
+
+/// Rebuild the Stats page: streak, average time to done and overdue count
+/// above a heatmap of completions, archived tasks included.
+fn fill_stats_page(list: &GtkBox, ctx: &Rc<RowContext>) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+
+    let now = Local::now().naive_local();
+    let archive = archive::Archive::load_or_empty();
+    let weeks = ctx.cfg.stats_weeks;
+    let stats = {
+        let store = ctx.store.borrow();
+        stats::Stats::compute(store.items.iter().chain(archive.tasks()), weeks, now)
+    };
+
+    // Summary tiles
+    let tiles = GtkBox::new(Orientation::Horizontal, 6);
+    tiles.set_homogeneous(true);
+    let average = stats
+        .average
+        .map(stats::format_span)
+        .unwrap_or_else(|| "–".to_string());
+    for (value, caption, alert) in [
+        (stats.streak.to_string(), "day streak", false),
+        (average, "avg. to done", false),
+        (stats.overdue.to_string(), "overdue", stats.overdue > 0),
+    ] {
+        let tile = GtkBox::new(Orientation::Vertical, 0);
+        tile.add_css_class("zenith-todo-stat");
+        let value = Label::new(Some(&value));
+        value.add_css_class("zenith-todo-stat-value");
+        if alert {
+            value.add_css_class("zenith-todo-stat-alert");
+        }
+        let caption = Label::new(Some(caption));
+        caption.add_css_class("zenith-todo-stat-caption");
+        tile.append(&value);
+        tile.append(&caption);
+        tiles.append(&tile);
+    }
+    list.append(&tiles);
+
+    let heading = GtkBox::new(Orientation::Horizontal, 8);
+    heading.add_css_class("zenith-todo-time-heading");
+    let name = Label::new(Some(&format!("Last {} weeks", weeks)));
+    name.set_hexpand(true);
+    name.set_halign(Align::Start);
+    heading.append(&name);
+    heading.append(&Label::new(Some(&format!("{} done", stats.total()))));
+    list.append(&heading);
+
+    list.append(&build_heatmap(stats.first_day, stats.per_day, weeks));
+}
+
+/// One column per week, Monday at the top; brighter cells mean more tasks
+/// done that day. Hovering a cell shows its date and count.
+fn build_heatmap(first_day: NaiveDate, per_day: Vec<u32>, weeks: u32) -> gtk4::DrawingArea {
+    let step = HEAT_CELL + HEAT_GAP;
+    let area = gtk4::DrawingArea::new();
+    area.add_css_class("zenith-todo-heatmap");
+    area.set_halign(Align::Center);
+    area.set_content_width((weeks as f64 * step - HEAT_GAP) as i32);
+    area.set_content_height((7.0 * step - HEAT_GAP) as i32);
+
+    let per_day = Rc::new(per_day);
+    let max = per_day.iter().copied().max().unwrap_or(0).max(1);
+    area.set_draw_func({
+        let per_day = Rc::clone(&per_day);
+        move |area, cr, _, _| {
+            // The CSS `color` of the heatmap, stronger with the share of the
+            // best day and faint for days with nothing done.
+            let color = area.color();
+            let (r, g, b) = (color.red(), color.green(), color.blue());
+            for (day, &count) in per_day.iter().enumerate() {
+                let (col, row) = ((day / 7) as f64, (day % 7) as f64);
+                let level = match count {
+                    0 => 0.08,
+                    n => 0.3 + 0.7 * n as f64 / max as f64,
+                };
+                cr.set_source_rgba(r.into(), g.into(), b.into(), level * color.alpha() as f64);
+                cr.rectangle(col * step, row * step, HEAT_CELL, HEAT_CELL);
+                let _ = cr.fill();
+            }
+        }
+    });
+
+    area.set_has_tooltip(true);
+    area.connect_query_tooltip(move |_, x, y, _, tooltip| {
+        let (col, row) = ((x as f64 / step) as usize, (y as f64 / step) as usize);
+        let day = col * 7 + row;
+        let Some(&count) = per_day.get(day).filter(|_| row < 7) else {
+            return false;
+        };
+        let date = first_day + Duration::days(day as i64);
+        let done = match count {
+            0 => "nothing done".to_string(),
+            1 => "1 task done".to_string(),
+            n => format!("{} tasks done", n),
+        };
+        tooltip.set_text(Some(&format!("{} · {}", date.format("%a %d %b"), done)));
+        true
+    });
+    area
+}
+
+/// Rebuild the archive view, newest first, each entry with a Restore button.
+fn fill_archive_list(list: &GtkBox, ctx: &Rc<RowContext>) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+
+    let archive = archive::Archive::load_or_empty();
+    if archive.items.is_empty() {
+        let empty = Label::new(Some("Nothing archived yet"));
+        empty.add_css_class("zenith-todo-empty");
+        list.append(&empty);
+        return;
+    }
+
+    for item in archive.tasks().rev() {
+        let row = GtkBox::new(Orientation::Horizontal, 8);
+        row.add_css_class("zenith-todo-row");
+        row.add_css_class("zenith-todo-archive-row");
+
+        let label = Label::new(Some(&item.text));
+        label.set_hexpand(true);
+        label.set_halign(Align::Start);
+        label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+        label.add_css_class("zenith-todo-text");
+        row.append(&label);
+
+        if let Some(at) = item.completed_at {
+            let when = Label::new(Some(&at.format("%d %b").to_string()));
+            when.add_css_class("zenith-todo-archive-date");
+            when.set_tooltip_text(Some(&format!(
+                "Completed {}",
+                at.format("%a %d %b %Y %H:%M")
+            )));
+            row.append(&when);
+        }
+
+        let restore_btn = Button::with_label("Restore");
+        restore_btn.add_css_class("zenith-todo-restore-btn");
+        let ctx_c = Rc::clone(ctx);
+        let (id, completed_at) = (item.id, item.completed_at);
+        restore_btn.connect_clicked(move |_| {
+            let mut archive = match archive::Archive::load() {
+                Ok(a) => a,
+                Err(e) => {
+                    log::error!("{e:#}");
+                    return;
+                }
+            };
+            let Some(entry) = archive.take(id, completed_at) else {
+                return;
+            };
+            if let Err(e) = archive.save() {
+                log::error!("{e:#}");
+                return;
+            }
+            // Back into the list it was archived from, if that still exists.
+            let mut s = ctx_c.store.borrow_mut();
+            let mut archived = entry.item;
+            archived.list = entry
+                .from_list
+                .as_deref()
+                .and_then(|name| s.list_index(name))
+                .unwrap_or(s.active);
+            let mut item = archived.clone();
+            item.reopen();
+            if s.find(item.id).is_some() {
+                item.id = s.next_id();
+            }
+            s.checkpoint();
+            s.checkpoint_archive(undo::ArchiveMove {
+                archived: Vec::new(),
+                restored: vec![archived],
+            });
+            s.items.push(item);
+            s.save();
+            drop(s);
+            ctx_c.refresh();
+        });
+        row.append(&restore_btn);
+
+        list.append(&row);
+    }
+}
@@ -0,0 +1,636 @@
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+
+use super::{ordinal, Due, Recurrence, TodoItem};
+
+/// Everything the add-task entry understood about a line of input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedTask {
+    pub text: String,
+    pub priority: u8,
+    pub due: Option<Due>,
+    pub recurrence: Option<Recurrence>,
+    pub remind: Option<NaiveDateTime>,
+    pub project: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl ParsedTask {
+    /// Short human-readable summary of the extracted metadata, used for the
+    /// live preview chip. `None` when nothing beyond plain text was found.
+    pub fn summary(&self, today: NaiveDate) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(due) = self.due {
+            parts.push(format!("◷ {}", due.describe(today)));
+        }
+        if let Some(rec) = self.recurrence {
+            parts.push(format!("↻ {}", rec));
+        }
+        if let Some(at) = self.remind {
+            let at = Due {
+                date: at.date(),
+                time: Some(at.time()),
+            };
+            parts.push(format!("⏰ {}", at.describe(today)));
+        }
+        if self.priority > 0 {
+            parts.push(format!("P{}", self.priority));
+        }
+        if let Some(ref p) = self.project {
+            parts.push(format!("+{}", p));
+        }
+        if !self.tags.is_empty() {
+            let tags: Vec<String> = self.tags.iter().map(|t| format!("#{}", t)).collect();
+            parts.push(tags.join(" "));
+        }
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join("  ·  "))
+        }
+    }
+}
+
+/// Parse "N:text" for priority shorthand. Returns (priority, clean_text).
+pub fn parse_priority(input: &str) -> (u8, String) {
+    if input.len() >= 2 {
+        let first = input.as_bytes()[0];
+        if first.is_ascii_digit() && input.as_bytes()[1] == b':' {
+            let prio = first - b'0';
+            return (prio, input[2..].trim().to_string());
+        }
+    }
+    (0, input.to_string())
+}
+
+/// Parse a full entry line: the priority prefix plus natural-language date
+/// phrases such as "tomorrow 9am", "next fri", "in 3 days" or "every monday".
+///
+/// A `remind:<when>` token sets an explicit reminder, where `<when>` is a
+/// time of day (`9am`, `14:30`, on the due date if there is one), an offset
+/// from now (`30m`, `2h`, `1d`) or a full `2026-10-20T09:00` timestamp.
+///
+/// `+project` and `#tag` tokens are collected into their own fields; only the
+/// first project counts.
+///
+/// Recognised phrases are removed from the task text. Relative phrases are
+/// resolved against `now`. Single words that also read as plain English
+/// ("daily", "noon") only count inside a phrase ("every day", "at noon"), so
+/// "Write daily report" keeps its text.
+pub fn parse_task(input: &str, now: NaiveDateTime) -> ParsedTask {
+    let (priority, rest) = parse_priority(input.trim());
+    let today = now.date();
+
+    let tokens: Vec<&str> = rest.split_whitespace().collect();
+    let mut kept: Vec<&str> = Vec::with_capacity(tokens.len());
+    let mut date: Option<NaiveDate> = None;
+    let mut time: Option<NaiveTime> = None;
+    let mut recurrence: Option<Recurrence> = None;
+    let mut remind: Option<RemindSpec> = None;
+    let mut project: Option<String> = None;
+    let mut tags: Vec<String> = Vec::new();
+
+    let mut i = 0;
+    while i < tokens.len() {
+        if project.is_none() {
+            if let Some(name) = tokens[i].strip_prefix('+').and_then(label_name) {
+                project = Some(name);
+                i += 1;
+                continue;
+            }
+        }
+        if let Some(name) = tokens[i].strip_prefix('#').and_then(label_name) {
+            if !tags.contains(&name) {
+                tags.push(name);
+            }
+            i += 1;
+            continue;
+        }
+
+        let window: Vec<String> = tokens[i..].iter().take(5).map(|t| normalise(t)).collect();
+
+        if remind.is_none() {
+            if let Some(spec) = window[0].strip_prefix("remind:").and_then(parse_remind) {
+                remind = Some(spec);
+                i += 1;
+                continue;
+            }
+        }
+
+        if let Some((consumed, found)) = match_phrase(&window, now) {
+            let applied = match found {
+                Found::Date(d) if date.is_none() => {
+                    date = Some(d);
+                    true
+                }
+                Found::Time(t) if time.is_none() => {
+                    time = Some(t);
+                    true
+                }
+                Found::DateTime(dt) if date.is_none() && time.is_none() => {
+                    date = Some(dt.date());
+                    time = Some(dt.time());
+                    true
+                }
+                Found::Recur(r) if recurrence.is_none() => {
+                    recurrence = Some(r);
+                    true
+                }
+                _ => false,
+            };
+            if applied {
+                i += consumed;
+                continue;
+            }
+        }
+
+        kept.push(tokens[i]);
+        i += 1;
+    }
+
+    // A bare time means the next time the clock shows it.
+    if date.is_none() {
+        if let Some(t) = time {
+            date = Some(if t > now.time() {
+                today
+            } else {
+                today + Duration::days(1)
+            });
+        }
+    }
+
+    // A bare recurrence starts at its first occurrence from today onwards.
+    if date.is_none() {
+        if let Some(rec) = recurrence {
+            date = Some(rec.first_on_or_after(today));
+        }
+    }
+
+    let remind = remind.and_then(|spec| match spec {
+        RemindSpec::In(offset) => now.checked_add_signed(offset).map(round_minute),
+        RemindSpec::At(t) => Some(match date {
+            Some(d) => d.and_time(t),
+            None if t > now.time() => today.and_time(t),
+            None => (today + Duration::days(1)).and_time(t),
+        }),
+        RemindSpec::On(dt) => Some(dt),
+    });
+
+    let text = if kept.is_empty() {
+        rest.trim().to_string()
+    } else {
+        kept.join(" ")
+    };
+
+    ParsedTask {
+        text,
+        priority,
+        due: date.map(|date| Due { date, time }),
+        recurrence,
+        remind,
+        project,
+        tags,
+    }
+}
+
+/// Validate the name part of a `+project` / `#tag` token. Names must start
+/// with a letter so "+1" and issue references like "#42" stay plain text.
+fn label_name(raw: &str) -> Option<String> {
+    let name = raw.trim_end_matches([',', '.', ';', '!', '?', ':']);
+    let valid = name.chars().next().is_some_and(char::is_alphabetic)
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/' | '.'));
+    valid.then(|| name.to_string())
+}
+
+/// Render an item back into entry syntax, the inverse of [`parse_task`], so
+/// editing a task keeps its metadata.
+pub fn format_task(item: &TodoItem) -> String {
+    let mut parts = Vec::new();
+    if (1..=9).contains(&item.priority) {
+        parts.push(format!("{}:{}", item.priority, item.text));
+    } else {
+        parts.push(item.text.clone());
+    }
+    if let Some(ref p) = item.project {
+        parts.push(format!("+{}", p));
+    }
+    parts.extend(item.tags.iter().map(|t| format!("#{}", t)));
+    if let Some(due) = item.due {
+        parts.push(due.date.format("%Y-%m-%d").to_string());
+        if let Some(t) = due.time {
+            parts.push(t.format("%H:%M").to_string());
+        }
+    }
+    if let Some(rec) = item.recurrence {
+        parts.push(match rec {
+            Recurrence::Daily => "every day".to_string(),
+            Recurrence::Weekdays => "every weekday".to_string(),
+            Recurrence::Weekly(wd) => format!("every {}", wd.to_string().to_lowercase()),
+            Recurrence::Monthly(day) => format!("every {}", ordinal(day)),
+            Recurrence::EveryDays(n) => format!("every {} days", n),
+            Recurrence::AfterCompletion(n) => format!("every {} days after completion", n),
+        });
+    }
+    if let Some(at) = item.remind {
+        parts.push(format!("remind:{}", at.format("%Y-%m-%dT%H:%M")));
+    }
+    parts.join(" ")
+}
+
+// ─── Phrase Matching ─────────────────────────────────────────────────────────
+
+enum Found {
+    Date(NaiveDate),
+    Time(NaiveTime),
+    DateTime(NaiveDateTime),
+    Recur(Recurrence),
+}
+
+/// Lowercase a token and strip trailing punctuation so "Friday," matches.
+fn normalise(token: &str) -> String {
+    token.trim_end_matches([',', '.', ';', '!']).to_lowercase()
+}
+
+/// Try to match a date/time phrase at the start of `w` (up to five
+/// normalised tokens). Returns the number of tokens consumed.
+fn match_phrase(w: &[String], now: NaiveDateTime) -> Option<(usize, Found)> {
+    let today = now.date();
+    let first = w.first()?.as_str();
+    let second = w.get(1).map(String::as_str);
+    let third = w.get(2).map(String::as_str);
+
+    match first {
+        "today" => return Some((1, Found::Date(today))),
+        "tomorrow" | "tmrw" | "tmr" => return Some((1, Found::Date(today + Duration::days(1)))),
+        "next" | "this" | "on" => {
+            if let Some(wd) = second.and_then(parse_weekday) {
+                return Some((2, Found::Date(next_weekday(today, wd))));
+            }
+            if first == "next" {
+                match second {
+                    Some("week") => return Some((2, Found::Date(today + Duration::days(7)))),
+                    Some("month") => return add_months(today, 1).map(|d| (2, Found::Date(d))),
+                    _ => {}
+                }
+            }
+        }
+        "at" => {
+            let time = match second {
+                Some("noon") => Some(hm(12, 0)),
+                Some("midnight") => Some(hm(0, 0)),
+                Some(s) => parse_time(s),
+                None => None,
+            };
+            if let Some(t) = time {
+                return Some((2, Found::Time(t)));
+            }
+        }
+        "in" => {
+            if let Some((consumed, amount, unit)) = parse_amount(second, third) {
+                let found = match unit {
+                    Unit::Months => Found::Date(add_months(today, amount as u32)?),
+                    Unit::Days | Unit::Weeks => {
+                        Found::Date(today.checked_add_signed(unit.duration(amount)?)?)
+                    }
+                    Unit::Minutes | Unit::Hours => Found::DateTime(round_minute(
+                        now.checked_add_signed(unit.duration(amount)?)?,
+                    )),
+                };
+                return Some((1 + consumed, found));
+            }
+        }
+        "every" => return match_every(&w[1..], today),
+        _ => {}
+    }
+
+    if let Some(t) = parse_time(first) {
+        return Some((1, Found::Time(t)));
+    }
+    if let Ok(d) = NaiveDate::parse_from_str(first, "%Y-%m-%d") {
+        return Some((1, Found::Date(d)));
+    }
+    None
+}
+
+/// Match the tail of an "every …" phrase. `rest` starts after "every".
+fn match_every(rest: &[String], today: NaiveDate) -> Option<(usize, Found)> {
+    let second = rest.first()?.as_str();
+    let third = rest.get(1).map(String::as_str);
+    let rec = match second {
+        "day" => Recurrence::Daily,
+        "weekday" | "weekdays" => Recurrence::Weekdays,
+        "week" => Recurrence::Weekly(today.weekday()),
+        "month" => Recurrence::Monthly(today.day()),
+        _ => {
+            if let Some(wd) = parse_weekday(second) {
+                Recurrence::Weekly(wd)
+            } else if let Some(day) = parse_ordinal(second) {
+                Recurrence::Monthly(day)
+            } else if let Some((consumed, amount, unit)) = parse_amount(Some(second), third) {
+                let days = match unit {
+                    Unit::Days => amount,
+                    Unit::Weeks => amount * 7,
+                    _ => return None,
+                };
+                if days == 0 {
+                    return None;
+                }
+                let days = days as u32;
+
+                // "every 3 days after completion" counts from the check-off.
+                let tail: Vec<&str> = rest[consumed..].iter().map(String::as_str).collect();
+                return Some(match tail.as_slice() {
+                    ["after", "completion" | "done", ..] => (
+                        1 + consumed + 2,
+                        Found::Recur(Recurrence::AfterCompletion(days)),
+                    ),
+                    ["after", ..] => (
+                        1 + consumed + 1,
+                        Found::Recur(Recurrence::AfterCompletion(days)),
+                    ),
+                    _ => (1 + consumed, Found::Recur(Recurrence::EveryDays(days))),
+                });
+            } else {
+                return None;
+            }
+        }
+    };
+    Some((2, Found::Recur(rec)))
+}
+
+/// The value of a `remind:` token, resolved once the due date is known.
+enum RemindSpec {
+    In(Duration),
+    At(NaiveTime),
+    On(NaiveDateTime),
+}
+
+fn parse_remind(spec: &str) -> Option<RemindSpec> {
+    if let Some(t) = parse_time(spec) {
+        return Some(RemindSpec::At(t));
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(&spec.replacen('t', " ", 1), "%Y-%m-%d %H:%M") {
+        return Some(RemindSpec::On(dt));
+    }
+    let (_, n, unit) = parse_amount(Some(spec), None)?;
+    unit.duration(n).map(RemindSpec::In)
+}
+
+/// Largest amount "in N days" and friends accept; more is almost certainly
+/// a typo, and chrono panics on durations far past this.
+const MAX_AMOUNT: i64 = 9999;
+
+enum Unit {
+    Minutes,
+    Hours,
+    Days,
+    Weeks,
+    Months,
+}
+
+impl Unit {
+    /// `n` of this unit, or `None` for months, which vary in length.
+    fn duration(&self, n: i64) -> Option<Duration> {
+        match self {
+            Unit::Minutes => Duration::try_minutes(n),
+            Unit::Hours => Duration::try_hours(n),
+            Unit::Days => Duration::try_days(n),
+            Unit::Weeks => Duration::try_weeks(n),
+            Unit::Months => None,
+        }
+    }
+}
+
+/// Parse "3 days", "a week" or the compact "3d" form. Returns
+/// (tokens consumed, amount, unit). Amounts are plain digits from 0 to
+/// [`MAX_AMOUNT`], so "-1" and signs are not amounts.
+fn parse_amount(a: Option<&str>, b: Option<&str>) -> Option<(usize, i64, Unit)> {
+    let a = a?;
+    let number = |s: &str| {
+        let valid = !s.is_empty() && s.bytes().all(|c| c.is_ascii_digit());
+        valid
+            .then(|| s.parse::<i64>().ok())
+            .flatten()
+            .filter(|n| *n <= MAX_AMOUNT)
+    };
+
+    let amount = match a {
+        "a" | "an" | "one" => Some(1),
+        _ => number(a),
+    };
+    if let (Some(n), Some(unit)) = (amount, b.and_then(parse_unit)) {
+        return Some((2, n, unit));
+    }
+
+    // Compact form: digits immediately followed by the unit.
+    let split = a.find(|c: char| !c.is_ascii_digit())?;
+    let (digits, unit) = a.split_at(split);
+    Some((1, number(digits)?, parse_unit(unit)?))
+}
+
+fn parse_unit(s: &str) -> Option<Unit> {
+    Some(match s {
+        "m" | "min" | "mins" | "minute" | "minutes" => Unit::Minutes,
+        "h" | "hr" | "hrs" | "hour" | "hours" => Unit::Hours,
+        "d" | "day" | "days" => Unit::Days,
+        "w" | "wk" | "wks" | "week" | "weeks" => Unit::Weeks,
+        "month" | "months" => Unit::Months,
+        _ => return None,
+    })
+}
+
+fn parse_weekday(s: &str) -> Option<Weekday> {
+    Some(match s {
+        "mon" | "monday" => Weekday::Mon,
+        "tue" | "tues" | "tuesday" => Weekday::Tue,
+        "wed" | "weds" | "wednesday" => Weekday::Wed,
+        "thu" | "thur" | "thurs" | "thursday" => Weekday::Thu,
+        "fri" | "friday" => Weekday::Fri,
+        "sat" | "saturday" => Weekday::Sat,
+        "sun" | "sunday" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+/// Parse "15th", "1st", "22nd" into a day of month.
+fn parse_ordinal(s: &str) -> Option<u32> {
+    let digits = s
+        .strip_suffix("st")
+        .or_else(|| s.strip_suffix("nd"))
+        .or_else(|| s.strip_suffix("rd"))
+        .or_else(|| s.strip_suffix("th"))?;
+    let day = digits.parse::<u32>().ok()?;
+    (1..=31).contains(&day).then_some(day)
+}
+
+/// Parse "9am", "9:30pm" or 24-hour "14:30".
+fn parse_time(s: &str) -> Option<NaiveTime> {
+    let (body, offset) = if let Some(b) = s.strip_suffix("am") {
+        (b, Some(0))
+    } else if let Some(b) = s.strip_suffix("pm") {
+        (b, Some(12))
+    } else {
+        (s, None)
+    };
+
+    let (h, m) = match body.split_once(':') {
+        Some((h, m)) if m.len() == 2 => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        // A bare number is only a time with an am/pm suffix.
+        None if offset.is_some() => (body.parse::<u32>().ok()?, 0),
+        _ => return None,
+    };
+
+    let hour = match offset {
+        Some(off) => {
+            if !(1..=12).contains(&h) {
+                return None;
+            }
+            h % 12 + off
+        }
+        None => h,
+    };
+    NaiveTime::from_hms_opt(hour, m, 0)
+}
+
+fn hm(h: u32, m: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(h, m, 0).unwrap_or(NaiveTime::MIN)
+}
+
+fn round_minute(dt: NaiveDateTime) -> NaiveDateTime {
+    dt.with_second(0)
+        .and_then(|d| d.with_nanosecond(0))
+        .unwrap_or(dt)
+}
+
+/// The first `wd` strictly after `from`.
+pub(super) fn next_weekday(from: NaiveDate, wd: Weekday) -> NaiveDate {
+    let ahead = (wd.num_days_from_monday() + 7 - from.weekday().num_days_from_monday()) % 7;
+    from + Duration::days(if ahead == 0 { 7 } else { ahead as i64 })
+}
+
+/// Add calendar months, clamping the day to the target month's length.
+/// `None` past the end of the calendar.
+pub(super) fn add_months(date: NaiveDate, months: u32) -> Option<NaiveDate> {
+    date.checked_add_months(Months::new(months))
+}
+
+/// Build a date, falling back to the last day of the month when `day` is out
+/// of range (e.g. the 31st in February). `None` for an invalid year or month.
+pub(super) fn clamp_day(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    (1..=day)
+        .rev()
+        .find_map(|d| NaiveDate::from_ymd_opt(year, month, d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sunday 18 Oct 2026, 10:00.
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 18)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap()
+    }
+
+    fn date(m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, m, d).unwrap()
+    }
+
+    #[test]
+    fn extracts_metadata_from_the_entry() {
+        let t = parse_task("2:Deploy server +ops #infra tomorrow at 9am", now());
+        assert_eq!(t.text, "Deploy server");
+        assert_eq!(t.priority, 2);
+        assert_eq!(t.project.as_deref(), Some("ops"));
+        assert_eq!(t.tags, vec!["infra".to_string()]);
+        assert_eq!(
+            t.due,
+            Some(Due {
+                date: date(10, 19),
+                time: Some(hm(9, 0)),
+            })
+        );
+
+        let t = parse_task("Water plants every 3 days after completion", now());
+        assert_eq!(t.text, "Water plants");
+        assert_eq!(t.recurrence, Some(Recurrence::AfterCompletion(3)));
+        assert_eq!(t.due.map(|d| d.date), Some(date(10, 18)));
+
+        let t = parse_task("Pay rent in 2 months remind:9am", now());
+        assert_eq!(t.due.map(|d| d.date), Some(date(12, 18)));
+        assert_eq!(t.remind, Some(date(12, 18).and_time(hm(9, 0))));
+    }
+
+    #[test]
+    fn plain_words_are_not_keywords() {
+        for input in [
+            "Write daily report",
+            "Send weekly update",
+            "Monthly budget review",
+            "Watch midnight movie",
+            "Lunch at noonish",
+        ] {
+            let t = parse_task(input, now());
+            assert_eq!(t.text, input);
+            assert_eq!((t.due, t.recurrence), (None, None), "{input}");
+        }
+        let t = parse_task("Backup every day at midnight", now());
+        assert_eq!(t.text, "Backup");
+        assert_eq!(t.recurrence, Some(Recurrence::Daily));
+        assert_eq!(t.due.and_then(|d| d.time), Some(hm(0, 0)));
+    }
+
+    #[test]
+    fn absurd_amounts_stay_text() {
+        for input in [
+            "Plan in 99999999999 days",
+            "Plan in -1 months",
+            "Plan in 9223372036854775807 minutes",
+            "Plan every 99999999999 weeks",
+            "Plan remind:99999999999d",
+        ] {
+            let t = parse_task(input, now());
+            assert_eq!(t.text, input);
+            assert_eq!((t.due, t.remind, t.recurrence), (None, None, None));
+        }
+        let far = NaiveDate::MAX.and_hms_opt(10, 0, 0).unwrap();
+        assert_eq!(parse_task("Plan in 2 months", far).due, None);
+        assert_eq!(add_months(NaiveDate::MAX, 1), None);
+        assert_eq!(clamp_day(2026, 2, 31), Some(date(2, 28)));
+        assert_eq!(clamp_day(2026, 13, 1), None);
+    }
+
+    #[test]
+    fn format_task_round_trips() {
+        let item = TodoItem {
+            text: "Deploy server".into(),
+            priority: 2,
+            project: Some("ops".into()),
+            tags: vec!["infra".into()],
+            due: Some(Due {
+                date: date(10, 20),
+                time: Some(hm(14, 0)),
+            }),
+            recurrence: Some(Recurrence::Weekly(Weekday::Tue)),
+            remind: Some(date(10, 20).and_time(hm(13, 30))),
+            ..Default::default()
+        };
+        let line = format_task(&item);
+        assert_eq!(
+            line,
+            "2:Deploy server +ops #infra 2026-10-20 14:00 every tue \
+             remind:2026-10-20T13:30"
+        );
+        let back = parse_task(&line, now());
+        assert_eq!(back.text, item.text);
+        assert_eq!(back.priority, item.priority);
+        assert_eq!(back.project, item.project);
+        assert_eq!(back.tags, item.tags);
+        assert_eq!(back.due, item.due);
+        assert_eq!(back.recurrence, item.recurrence);
+        assert_eq!(back.remind, item.remind);
+    }
+}
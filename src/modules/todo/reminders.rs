@@ -0,0 +1,135 @@
+use chrono::{Duration, Local};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::{RefreshCallback, TodoStore};
+use crate::notify::Notifier;
+
+/// Longest single wait before a timer re-checks the wall clock. GLib timers
+/// run on the monotonic clock, so capping them keeps reminders on time after
+/// a suspend/resume.
+const MAX_WAIT_SECS: i64 = 15 * 60;
+
+const SNOOZE_MINUTES: i64 = 10;
+
+/// Schedules one GLib timeout per pending reminder and turns them into
+/// desktop notifications with "Done" / "Snooze" actions.
+pub struct Reminders {
+    notifier: Option<Notifier>,
+    store: Rc<RefCell<TodoStore>>,
+    refresh: RefreshCallback,
+    timers: RefCell<HashMap<u64, glib::SourceId>>,
+}
+
+impl Reminders {
+    pub fn new(store: Rc<RefCell<TodoStore>>, refresh: RefreshCallback) -> Rc<Self> {
+        Rc::new(Self {
+            notifier: Notifier::connect(),
+            store,
+            refresh,
+            timers: RefCell::new(HashMap::new()),
+        })
+    }
+
+    /// Cancel all timers and schedule every reminder still in the future.
+    /// Called after each store change and once on startup.
+    pub fn rearm(self: &Rc<Self>) {
+        for (_, source) in self.timers.borrow_mut().drain() {
+            source.remove();
+        }
+        if self.notifier.is_none() {
+            return;
+        }
+
+        let now = Local::now().naive_local();
+        let pending: Vec<u64> = self
+            .store
+            .borrow()
+            .items
+            .iter()
+            .filter(|t| !t.done && t.reminder_at().is_some_and(|at| at > now))
+            .map(|t| t.id)
+            .collect();
+
+        for id in pending {
+            self.arm(id);
+        }
+    }
+
+    fn arm(self: &Rc<Self>, id: u64) {
+        let Some(at) = self.store.borrow().find(id).and_then(|t| t.reminder_at()) else {
+            return;
+        };
+        let wait = (at - Local::now().naive_local())
+            .num_seconds()
+            .clamp(0, MAX_WAIT_SECS) as u32;
+
+        let this = Rc::downgrade(self);
+        let source = glib::timeout_add_seconds_local_once(wait, move || {
+            if let Some(this) = this.upgrade() {
+                this.timers.borrow_mut().remove(&id);
+                this.fire(id);
+            }
+        });
+        self.timers.borrow_mut().insert(id, source);
+    }
+
+    /// Timer callback: notify if the reminder is due, otherwise keep waiting.
+    fn fire(self: &Rc<Self>, id: u64) {
+        let now = Local::now().naive_local();
+        let (text, at) = {
+            let store = self.store.borrow();
+            match store.find(id) {
+                Some(t) if !t.done => match t.reminder_at() {
+                    Some(at) => (t.text.clone(), at),
+                    None => return,
+                },
+                _ => return,
+            }
+        };
+
+        if at > now {
+            self.arm(id);
+            return;
+        }
+
+        let Some(ref notifier) = self.notifier else {
+            return;
+        };
+        let this = Rc::downgrade(self);
+        notifier.notify(
+            "Task reminder",
+            &text,
+            &[("done", "Done"), ("snooze", "Snooze 10m")],
+            move |action| {
+                if let Some(this) = this.upgrade() {
+                    this.handle_action(id, action);
+                }
+            },
+        );
+    }
+
+    fn handle_action(&self, id: u64, action: &str) {
+        let now = Local::now().naive_local();
+        {
+            let mut store = self.store.borrow_mut();
+            if !matches!(action, "done" | "snooze") || store.find(id).is_none() {
+                return;
+            }
+            store.checkpoint();
+            if let Some(item) = store.find_mut(id) {
+                if action == "done" {
+                    item.complete(now);
+                } else {
+                    item.remind = Some(now + Duration::minutes(SNOOZE_MINUTES));
+                }
+            }
+            store.save();
+        }
+
+        if let Some(ref f) = *self.refresh.borrow() {
+            f();
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use chrono::NaiveDateTime;
+use std::cmp::Ordering;
+
+use super::TodoItem;
+use crate::config::SortMode;
+
+/// Every mode in dropdown order.
+pub const MODES: [SortMode; 5] = [
+    SortMode::Manual,
+    SortMode::Priority,
+    SortMode::Due,
+    SortMode::Created,
+    SortMode::Smart,
+];
+
+/// Labels for the sort dropdown, in [`MODES`] order.
+pub const LABELS: [&str; 5] = ["Manual", "Priority", "Due date", "Newest", "Smart"];
+
+pub fn index_of(mode: SortMode) -> u32 {
+    MODES.iter().position(|m| *m == mode).unwrap_or(0) as u32
+}
+
+pub fn from_index(index: u32) -> SortMode {
+    MODES.get(index as usize).copied().unwrap_or_default()
+}
+
+/// Indices into `items` in display order. Except in manual mode, pending
+/// tasks come before completed ones; ties keep the manual order.
+pub fn order(items: &[TodoItem], mode: SortMode, now: NaiveDateTime) -> Vec<usize> {
+    let mut idx: Vec<usize> = (0..items.len()).collect();
+    if mode == SortMode::Manual {
+        return idx;
+    }
+    idx.sort_by(|&a, &b| {
+        let (a, b) = (&items[a], &items[b]);
+        a.done.cmp(&b.done).then_with(|| compare(a, b, mode, now))
+    });
+    idx
+}
+
+fn compare(a: &TodoItem, b: &TodoItem, mode: SortMode, now: NaiveDateTime) -> Ordering {
+    match mode {
+        SortMode::Manual => Ordering::Equal,
+        // 0 means "no priority" and sorts after 9.
+        SortMode::Priority => {
+            let rank = |t: &TodoItem| if t.priority == 0 { 10 } else { t.priority };
+            rank(a).cmp(&rank(b))
+        }
+        SortMode::Due => match (a.due, b.due) {
+            (Some(x), Some(y)) => x.deadline().cmp(&y.deadline()),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+        SortMode::Created => b.created.cmp(&a.created),
+        SortMode::Smart => smart_score(b, now).total_cmp(&smart_score(a, now)),
+    }
+}
+
+/// Higher is more pressing. Taskwarrior's own urgency is used when the task
+/// has one; otherwise priority (up to 9), an approaching due date (up to 12,
+/// ramping up over the last two weeks) and age (up to 2, after two weeks)
+/// add up.
+pub fn smart_score(item: &TodoItem, now: NaiveDateTime) -> f64 {
+    if let Some(urgency) = item.urgency {
+        return urgency;
+    }
+    let mut score = 0.0;
+    if (1..=9).contains(&item.priority) {
+        score += f64::from(10 - item.priority);
+    }
+    if let Some(due) = item.due {
+        let hours_left = (due.deadline() - now).num_minutes() as f64 / 60.0;
+        score += 12.0 * (1.0 - (hours_left / (14.0 * 24.0)).clamp(0.0, 1.0));
+    }
+    if let Some(created) = item.created {
+        score += ((now - created).num_days() as f64 / 7.0).clamp(0.0, 2.0);
+    }
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::todo::Due;
+    use chrono::{Duration, NaiveDate, NaiveTime};
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 19)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap()
+    }
+
+    fn due(days: i64, time: Option<(u32, u32)>) -> Option<Due> {
+        Some(Due {
+            date: now().date() + Duration::days(days),
+            time: time.and_then(|(h, m)| NaiveTime::from_hms_opt(h, m, 0)),
+        })
+    }
+
+    fn task(priority: u8, due: Option<Due>, age_days: i64) -> TodoItem {
+        TodoItem {
+            priority,
+            due,
+            created: Some(now() - Duration::days(age_days)),
+            ..Default::default()
+        }
+    }
+
+    fn items() -> Vec<TodoItem> {
+        vec![
+            task(0, None, 1),
+            task(2, due(0, Some((18, 0))), 20),
+            TodoItem {
+                done: true,
+                ..task(1, due(-1, None), 0)
+            },
+            task(5, due(1, None), 3),
+            task(2, None, 5),
+        ]
+    }
+
+    #[test]
+    fn each_mode_orders_pending_tasks_first() {
+        let table = [
+            (SortMode::Manual, [0, 1, 2, 3, 4]),
+            // 1 and 4 tie on priority and keep their manual order; no
+            // priority sorts last.
+            (SortMode::Priority, [1, 4, 3, 0, 2]),
+            // Undated tasks go last, in manual order.
+            (SortMode::Due, [1, 3, 0, 4, 2]),
+            (SortMode::Created, [0, 3, 4, 1, 2]),
+            (SortMode::Smart, [1, 3, 4, 0, 2]),
+        ];
+        for (mode, expected) in table {
+            assert_eq!(order(&items(), mode, now()), expected, "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn taskwarrior_urgency_takes_precedence() {
+        let mut items = items();
+        items[0].urgency = Some(30.0);
+        items[1].urgency = Some(0.5);
+        assert_eq!(order(&items, SortMode::Smart, now()), [0, 3, 4, 1, 2]);
+
+        let overdue_and_urgent = TodoItem {
+            urgency: Some(1.0),
+            ..task(1, due(-3, None), 30)
+        };
+        assert_eq!(smart_score(&overdue_and_urgent, now()), 1.0);
+    }
+
+    #[test]
+    fn smart_score_parts() {
+        let cases = [
+            (task(0, None, 0), 0.0),
+            (task(1, None, 0), 9.0),
+            (task(9, None, 0), 1.0),
+            // Overdue counts in full, two weeks out not at all.
+            (task(0, due(-1, None), 0), 12.0),
+            (task(0, due(15, None), 0), 0.0),
+            // Age adds a point a week, up to two.
+            (task(0, None, 7), 1.0),
+            (task(0, None, 60), 2.0),
+        ];
+        for (item, expected) in cases {
+            let score = smart_score(&item, now());
+            assert!((score - expected).abs() < 1e-9, "{score} != {expected}");
+        }
+    }
+}
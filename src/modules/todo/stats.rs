@@ -0,0 +1,145 @@
+//! Completion statistics for the stats page, worked out from the
+//! completion timestamps on each task.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+use std::collections::HashMap;
+
+use super::TodoItem;
+
+pub struct Stats {
+    /// Monday of the first week in `per_day`.
+    pub first_day: NaiveDate,
+    /// Tasks completed on each day from `first_day` up to today.
+    pub per_day: Vec<u32>,
+    /// Consecutive days with a completion, ending today (or yesterday while
+    /// nothing is done yet today).
+    pub streak: u32,
+    /// Mean time from adding a task to checking it off.
+    pub average: Option<Duration>,
+    /// Pending tasks past their deadline.
+    pub overdue: usize,
+}
+
+impl Stats {
+    pub fn compute<'a>(
+        items: impl IntoIterator<Item = &'a TodoItem>,
+        weeks: u32,
+        now: NaiveDateTime,
+    ) -> Self {
+        let today = now.date();
+        let mut counts: HashMap<NaiveDate, u32> = HashMap::new();
+        let mut spans = Vec::new();
+        let mut overdue = 0;
+
+        for item in items {
+            for at in completion_times(item) {
+                *counts.entry(at.date()).or_default() += 1;
+            }
+            if let (true, None, Some(created), Some(done)) =
+                (item.done, item.recurrence, item.created, item.completed_at)
+            {
+                spans.push((done - created).max(Duration::zero()));
+            }
+            if !item.done && item.due.is_some_and(|d| d.deadline() < now) {
+                overdue += 1;
+            }
+        }
+
+        let this_monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+        let first_day = this_monday - Duration::weeks(weeks.max(1) as i64 - 1);
+        let per_day = first_day
+            .iter_days()
+            .take_while(|d| *d <= today)
+            .map(|d| counts.get(&d).copied().unwrap_or(0))
+            .collect();
+
+        let has = |d: &NaiveDate| counts.contains_key(d);
+        let start = if has(&today) {
+            today
+        } else {
+            today - Duration::days(1)
+        };
+        let streak = (0..)
+            .map(|n| start - Duration::days(n))
+            .take_while(has)
+            .count() as u32;
+
+        let average = (!spans.is_empty()).then(|| {
+            let total = spans.iter().fold(Duration::zero(), |a, b| a + *b);
+            total / spans.len() as i32
+        });
+
+        Self {
+            first_day,
+            per_day,
+            streak,
+            average,
+            overdue,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.per_day.iter().sum()
+    }
+}
+
+/// Every moment `item` was checked off: each occurrence of a recurring task,
+/// or the completion of a done one.
+fn completion_times(item: &TodoItem) -> impl Iterator<Item = NaiveDateTime> + '_ {
+    let done = item.completed_at.filter(|_| item.done);
+    item.completions.iter().copied().chain(done)
+}
+
+/// "3d 4h", "5h 12m" or "12m".
+pub fn format_span(d: Duration) -> String {
+    let minutes = d.num_minutes().max(0);
+    match (minutes / (24 * 60), minutes / 60 % 24) {
+        (0, 0) => format!("{}m", minutes),
+        (0, h) => format!("{}h {:02}m", h, minutes % 60),
+        (days, h) => format!("{}d {}h", days, h),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    fn done(created: NaiveDateTime, completed: NaiveDateTime) -> TodoItem {
+        TodoItem {
+            done: true,
+            created: Some(created),
+            completed_at: Some(completed),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn streak_heatmap_and_average() {
+        // Sunday 18 Oct; nothing done yet today, so the streak runs
+        // through yesterday.
+        let now = at(18, 9);
+        let items = vec![
+            done(at(15, 9), at(15, 21)),
+            done(at(15, 9), at(16, 9)),
+            done(at(16, 9), at(17, 9)),
+            done(at(10, 9), at(12, 9)),
+        ];
+        let stats = Stats::compute(&items, 2, now);
+        assert_eq!(
+            stats.first_day,
+            NaiveDate::from_ymd_opt(2026, 10, 5).unwrap()
+        );
+        assert_eq!(stats.per_day.len(), 14);
+        assert_eq!(stats.total(), 4);
+        assert_eq!(stats.streak, 3);
+        assert_eq!(stats.average, Some(Duration::hours(27)));
+        assert_eq!(format_span(Duration::hours(27)), "1d 3h");
+    }
+}
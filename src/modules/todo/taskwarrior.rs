@@ -0,0 +1,735 @@
+//! Taskwarrior as a todo backend, driven through the `task` command.
+//!
+//! Loading runs `task export`; saving compares the list with what was last
+//! loaded or written and issues `task import` (for new tasks, with a UUID
+//! picked up front), `task <uuid> modify`, `task <uuid> done` and
+//! `task <uuid> delete` for the differences, so fields Zenith does not know
+//! about (annotations, UDAs, waits…) are left untouched. The data directory
+//! is passed as `TASKDATA`.
+//!
+//! In the bar these commands run on a worker thread: saving returns at once
+//! and loading hands out the last known list, then reports back through
+//! [`TodoBackend::on_loaded`] when the export is in.
+//!
+//! Priorities map onto H/M/L by band (1–3, 4–6, 7–9) and read back as 2, 5
+//! and 8. Subtasks, reminders and Zenith recurrences are not stored.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{Local, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use gtk4::gio;
+use serde::Deserialize;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ffi::OsStr;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+
+use super::backend::TodoBackend;
+use super::{Due, TodoItem};
+
+/// Date format of `task export`.
+const EXPORT_DATE: &str = "%Y%m%dT%H%M%SZ";
+
+/// Pending tasks plus anything completed today.
+const FILTER: [&str; 8] = [
+    "(",
+    "status:pending",
+    "or",
+    "(",
+    "status:completed",
+    "end.after:today",
+    ")",
+    ")",
+];
+
+/// `$TASKDATA`, falling back to `~/.task`.
+pub fn default_data_dir() -> PathBuf {
+    std::env::var_os("TASKDATA")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".task")))
+        .unwrap_or_else(|| PathBuf::from(".task"))
+}
+
+/// Whether this thread runs the GTK main loop, which must never wait on
+/// `task`. The command line and tests have no loop and run it directly.
+fn on_main_loop() -> bool {
+    glib::MainContext::default().is_owner()
+}
+
+pub struct TaskwarriorBackend {
+    cmd: TaskCmd,
+    /// Tasks as last read from or written to Taskwarrior, by UUID.
+    known: RefCell<HashMap<String, TodoItem>>,
+    /// The list as last loaded or saved, handed out while a fresh export
+    /// runs in the background.
+    current: RefCell<Vec<TodoItem>>,
+    worker: Rc<Worker>,
+}
+
+impl TaskwarriorBackend {
+    pub fn new(data: PathBuf) -> Self {
+        let cmd = TaskCmd { data, taskrc: None };
+        Self {
+            worker: Rc::new(Worker::new(cmd.clone())),
+            cmd,
+            known: RefCell::new(HashMap::new()),
+            current: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// The `task` calls that bring Taskwarrior from `known` to `items`.
+    /// New tasks get their UUID here, so it is recorded before they exist.
+    fn plan(&self, items: &mut [TodoItem]) -> Vec<Call> {
+        let mut known = self.known.borrow_mut();
+        let mut seen = HashSet::new();
+        let mut calls = Vec::new();
+
+        for item in items.iter_mut() {
+            let uuid = match item.external_id.clone().filter(|id| is_uuid(id)) {
+                Some(uuid) => {
+                    calls.extend(update(&uuid, known.get(&uuid), item));
+                    uuid
+                }
+                None => {
+                    let uuid = glib::uuid_string_random().to_string();
+                    calls.push(create(&uuid, item));
+                    item.external_id = Some(uuid.clone());
+                    uuid
+                }
+            };
+            known.insert(uuid.clone(), item.clone());
+            seen.insert(uuid);
+        }
+
+        // Removing a completed task (e.g. archiving it) only hides it;
+        // Taskwarrior keeps the history. Pending tasks are deleted.
+        let removed: Vec<String> = known
+            .keys()
+            .filter(|uuid| !seen.contains(*uuid))
+            .cloned()
+            .collect();
+        for uuid in removed {
+            if known.remove(&uuid).is_some_and(|t| !t.done) {
+                calls.push(Call::new([uuid.as_str(), "delete"]));
+            }
+        }
+        calls
+    }
+}
+
+impl TodoBackend for TaskwarriorBackend {
+    fn load(&self) -> Result<Vec<TodoItem>> {
+        let items = if !on_main_loop() {
+            self.cmd.export()?
+        } else if let Some(items) = self.worker.fresh.take() {
+            items
+        } else {
+            // Show what we have; the worker reports back once the export
+            // has run.
+            self.worker.push(Job::Export);
+            return Ok(self.current.borrow().clone());
+        };
+
+        *self.known.borrow_mut() = items
+            .iter()
+            .filter_map(|t| Some((t.external_id.clone()?, t.clone())))
+            .collect();
+        *self.current.borrow_mut() = items.clone();
+        Ok(items)
+    }
+
+    fn save(&self, items: &mut [TodoItem]) -> Result<()> {
+        let calls = self.plan(items);
+        *self.current.borrow_mut() = items.to_vec();
+        if !on_main_loop() {
+            return self.cmd.apply(&calls);
+        }
+        if !calls.is_empty() {
+            self.worker.push(Job::Write(calls));
+        }
+        Ok(())
+    }
+
+    fn path(&self) -> &Path {
+        &self.cmd.data
+    }
+
+    fn on_loaded(&self, f: Box<dyn Fn()>) {
+        *self.worker.on_loaded.borrow_mut() = Some(f);
+    }
+}
+
+/// How to run `task` on one data directory; cheap to move to a worker.
+#[derive(Clone)]
+struct TaskCmd {
+    data: PathBuf,
+    /// `TASKRC` for the child instead of the inherited one.
+    taskrc: Option<PathBuf>,
+}
+
+impl TaskCmd {
+    /// Run `task` with `args`, feeding it `input`, and return its stdout.
+    fn run<S: AsRef<OsStr>>(&self, args: &[S], input: Option<&str>) -> Result<String> {
+        let mut cmd = Command::new("task");
+        cmd.env("TASKDATA", &self.data)
+            .args(["rc.confirmation=off", "rc.verbose=nothing"])
+            .args(args)
+            .stdin(if input.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(ref rc) = self.taskrc {
+            cmd.env("TASKRC", rc);
+        }
+        let mut child = cmd
+            .spawn()
+            .context("Failed to run `task`; is Taskwarrior installed?")?;
+        if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+            stdin.write_all(input.as_bytes())?;
+        }
+        let out = child.wait_with_output()?;
+        if !out.status.success() {
+            bail!(
+                "`task` exited with {}: {}",
+                out.status,
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+    }
+
+    /// Pending and recently completed tasks, most urgent first.
+    fn export(&self) -> Result<Vec<TodoItem>> {
+        let mut args = vec!["rc.json.array=on"];
+        args.extend(FILTER);
+        args.push("export");
+        let out = self.run(&args, None)?;
+
+        let tasks: Vec<ExportedTask> =
+            serde_json::from_str(&out).context("Unexpected `task export` output")?;
+        let mut items: Vec<TodoItem> = tasks.into_iter().map(ExportedTask::into_item).collect();
+
+        // Most urgent first; completed tasks at the end.
+        items.sort_by(|a, b| {
+            a.done.cmp(&b.done).then(
+                b.urgency
+                    .partial_cmp(&a.urgency)
+                    .unwrap_or(std::cmp::Ordering::Equal),
+            )
+        });
+        Ok(items)
+    }
+
+    /// Make `calls` in order, stopping at the first error.
+    fn apply(&self, calls: &[Call]) -> Result<()> {
+        for call in calls {
+            self.run(&call.args, call.input.as_deref())?;
+        }
+        Ok(())
+    }
+}
+
+/// One `task` invocation.
+#[derive(Debug, PartialEq)]
+struct Call {
+    args: Vec<String>,
+    /// Fed to stdin, for `task import`.
+    input: Option<String>,
+}
+
+impl Call {
+    fn new<S: ToString>(args: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            args: args.into_iter().map(|a| a.to_string()).collect(),
+            input: None,
+        }
+    }
+}
+
+/// Import `item` as a new task with the UUID we picked for it.
+fn create(uuid: &str, item: &TodoItem) -> Call {
+    let now = Local::now().naive_local();
+    let mut task = serde_json::json!({
+        "uuid": uuid,
+        "description": item.text,
+        "status": if item.done { "completed" } else { "pending" },
+        "entry": format_utc(item.created.unwrap_or(now)),
+    });
+    if item.done {
+        task["end"] = format_utc(item.completed_at.unwrap_or(now)).into();
+    }
+    if item.priority > 0 {
+        task["priority"] = priority_letter(item.priority).into();
+    }
+    if let Some(due) = item.due {
+        task["due"] = format_utc(due_time(due)).into();
+    }
+    if let Some(ref project) = item.project {
+        task["project"] = project.as_str().into();
+    }
+    if !item.tags.is_empty() {
+        task["tags"] = item.tags.clone().into();
+    }
+    Call {
+        args: vec!["import".to_string(), "-".to_string()],
+        input: Some(task.to_string()),
+    }
+}
+
+/// The calls that bring the task `uuid` from `prev` to `item`.
+///
+/// Without `prev` the task is one we dropped earlier (deleted or archived)
+/// that came back through undo or a restore, so its status in Taskwarrior
+/// is unknown and is set outright.
+fn update(uuid: &str, prev: Option<&TodoItem>, item: &TodoItem) -> Vec<Call> {
+    let was_done = prev.is_some_and(|p| p.done);
+    let mut calls = Vec::new();
+
+    let mut args = attribute_changes(prev, item);
+    if prev.is_none() {
+        let status = if item.done { "completed" } else { "pending" };
+        args.push(format!("status:{status}"));
+    } else if was_done && !item.done {
+        args.push("status:pending".to_string());
+    }
+    if prev.is_none_or(|p| p.text != item.text) && !item.text.is_empty() {
+        args.push("--".to_string());
+        args.push(item.text.clone());
+    }
+    if !args.is_empty() {
+        let mut cmd = vec![uuid.to_string(), "modify".to_string()];
+        cmd.extend(args);
+        calls.push(Call::new(cmd));
+    }
+
+    if item.done && !was_done && prev.is_some() {
+        calls.push(Call::new([uuid, "done"]));
+    }
+    calls
+}
+
+/// Work for the worker thread.
+enum Job {
+    Write(Vec<Call>),
+    Export,
+}
+
+/// Runs `task` off the GTK thread with `gio::spawn_blocking`, one job at a
+/// time so writes land in order and an export sees all of them.
+struct Worker {
+    cmd: TaskCmd,
+    jobs: RefCell<VecDeque<Job>>,
+    busy: Cell<bool>,
+    /// A finished export not yet picked up by `load`.
+    fresh: RefCell<Option<Vec<TodoItem>>>,
+    on_loaded: RefCell<Option<Box<dyn Fn()>>>,
+}
+
+impl Worker {
+    fn new(cmd: TaskCmd) -> Self {
+        Self {
+            cmd,
+            jobs: RefCell::new(VecDeque::new()),
+            busy: Cell::new(false),
+            fresh: RefCell::new(None),
+            on_loaded: RefCell::new(None),
+        }
+    }
+
+    fn push(self: &Rc<Self>, job: Job) {
+        let mut jobs = self.jobs.borrow_mut();
+        if matches!(job, Job::Export) && jobs.iter().any(|j| matches!(j, Job::Export)) {
+            return;
+        }
+        jobs.push_back(job);
+        drop(jobs);
+        self.next();
+    }
+
+    fn next(self: &Rc<Self>) {
+        if self.busy.get() {
+            return;
+        }
+        let Some(job) = self.jobs.borrow_mut().pop_front() else {
+            return;
+        };
+        self.busy.set(true);
+
+        let this = Rc::clone(self);
+        glib::spawn_future_local(async move {
+            let cmd = this.cmd.clone();
+            let export = matches!(job, Job::Export);
+            let result = gio::spawn_blocking(move || match job {
+                Job::Write(calls) => cmd.apply(&calls).map(|()| None),
+                Job::Export => cmd.export().map(Some),
+            })
+            .await
+            .unwrap_or_else(|_| Err(anyhow!("Taskwarrior thread panicked")));
+            this.busy.set(false);
+
+            match result {
+                Err(e) if export => log::error!(
+                    "Failed to load tasks from {}: {e:#}",
+                    this.cmd.data.display()
+                ),
+                // Show what Taskwarrior really has rather than the list that
+                // failed to go out.
+                Err(e) => {
+                    log::error!("Failed to save tasks to {}: {e:#}", this.cmd.data.display());
+                    this.push(Job::Export);
+                }
+                // Writes queued meanwhile are not in this export yet.
+                Ok(Some(_)) if !this.jobs.borrow().is_empty() => this.push(Job::Export),
+                Ok(Some(items)) => {
+                    *this.fresh.borrow_mut() = Some(items);
+                    if let Some(ref f) = *this.on_loaded.borrow() {
+                        f();
+                    }
+                }
+                Ok(None) => {}
+            }
+            this.next();
+        });
+    }
+}
+
+/// The fields of a `task export` entry that Zenith uses.
+#[derive(Debug, Deserialize)]
+struct ExportedTask {
+    uuid: String,
+    description: String,
+    status: String,
+    #[serde(default)]
+    priority: Option<String>,
+    #[serde(default)]
+    due: Option<String>,
+    #[serde(default)]
+    project: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    entry: Option<String>,
+    #[serde(default)]
+    end: Option<String>,
+    #[serde(default)]
+    urgency: f64,
+}
+
+impl ExportedTask {
+    fn into_item(self) -> TodoItem {
+        let done = self.status == "completed";
+        TodoItem {
+            text: self.description,
+            done,
+            priority: match self.priority.as_deref() {
+                Some("H") => 2,
+                Some("M") => 5,
+                Some("L") => 8,
+                _ => 0,
+            },
+            due: self.due.as_deref().and_then(parse_date).map(due_from_local),
+            project: self.project,
+            tags: self.tags,
+            created: self.entry.as_deref().and_then(parse_date),
+            completed_at: self.end.as_deref().and_then(parse_date).filter(|_| done),
+            external_id: Some(self.uuid),
+            urgency: Some(self.urgency),
+            ..Default::default()
+        }
+    }
+}
+
+/// `task` arguments that turn `prev` into `item` for priority, due date,
+/// project and tags. With no `prev`, every set field is included.
+fn attribute_changes(prev: Option<&TodoItem>, item: &TodoItem) -> Vec<String> {
+    let mut args = Vec::new();
+    let differs = |f: &dyn Fn(&TodoItem) -> String| prev.is_none_or(|p| f(p) != f(item));
+    let fresh = prev.is_none();
+
+    let priority = |t: &TodoItem| priority_letter(t.priority).to_string();
+    if differs(&priority) && !(fresh && item.priority == 0) {
+        args.push(format!("priority:{}", priority(item)));
+    }
+
+    let due = |t: &TodoItem| t.due.map(format_due).unwrap_or_default();
+    if differs(&due) && !(fresh && item.due.is_none()) {
+        args.push(format!("due:{}", due(item)));
+    }
+
+    let project = |t: &TodoItem| t.project.clone().unwrap_or_default();
+    if differs(&project) && !(fresh && item.project.is_none()) {
+        args.push(format!("project:{}", project(item)));
+    }
+
+    let old_tags: &[String] = prev.map_or(&[], |p| &p.tags);
+    for tag in item.tags.iter().filter(|t| !old_tags.contains(t)) {
+        args.push(format!("+{}", tag));
+    }
+    for tag in old_tags.iter().filter(|t| !item.tags.contains(t)) {
+        args.push(format!("-{}", tag));
+    }
+    args
+}
+
+fn priority_letter(priority: u8) -> &'static str {
+    match priority {
+        1..=3 => "H",
+        4..=6 => "M",
+        7..=9 => "L",
+        _ => "",
+    }
+}
+
+/// Parse an export timestamp (UTC) into local time.
+fn parse_date(s: &str) -> Option<NaiveDateTime> {
+    let utc = NaiveDateTime::parse_from_str(s, EXPORT_DATE).ok()?;
+    Some(
+        Utc.from_utc_datetime(&utc)
+            .with_timezone(&Local)
+            .naive_local(),
+    )
+}
+
+/// Midnight and end-of-day due times are treated as date-only.
+fn due_from_local(at: NaiveDateTime) -> Due {
+    let all_day = at.time() == NaiveTime::MIN || at.time() == end_of_day();
+    Due {
+        date: at.date(),
+        time: (!all_day).then_some(at.time()),
+    }
+}
+
+/// Local ISO time for `due:`.
+fn format_due(due: Due) -> String {
+    due_time(due).format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+/// When `due` falls due. Date-only tasks are due at the end of the day, as
+/// in Zenith.
+fn due_time(due: Due) -> NaiveDateTime {
+    due.date.and_time(due.time.unwrap_or_else(end_of_day))
+}
+
+/// Local time `at` in the export format, for `task import`.
+fn format_utc(at: NaiveDateTime) -> String {
+    let utc = match Local.from_local_datetime(&at).earliest() {
+        Some(local) => local.with_timezone(&Utc).naive_utc(),
+        None => at,
+    };
+    utc.format(EXPORT_DATE).to_string()
+}
+
+fn end_of_day() -> NaiveTime {
+    NaiveTime::from_hms_opt(23, 59, 59).unwrap_or(NaiveTime::MIN)
+}
+
+fn is_uuid(s: &str) -> bool {
+    s.len() == 36
+        && s.chars().enumerate().all(|(i, c)| {
+            matches!(i, 8 | 13 | 18 | 23) == (c == '-') && (c == '-' || c.is_ascii_hexdigit())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn utc(local: NaiveDateTime) -> String {
+        Local
+            .from_local_datetime(&local)
+            .unwrap()
+            .with_timezone(&Utc)
+            .format(EXPORT_DATE)
+            .to_string()
+    }
+
+    fn local(y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, s)
+            .unwrap()
+    }
+
+    #[test]
+    fn export_entries_become_items() {
+        let json = format!(
+            r#"[
+                {{"id":1,"uuid":"5e7c9b8a-1f2d-4c3b-9a8e-7d6c5b4a3f2e","description":"Deploy server",
+                  "status":"pending","priority":"H","project":"ops","tags":["work"],
+                  "due":"{}","urgency":9.2}},
+                {{"id":0,"uuid":"0a1b2c3d-4e5f-4a6b-8c7d-9e0f1a2b3c4d","description":"Call mom",
+                  "status":"completed","end":"{}","urgency":0}}
+            ]"#,
+            utc(local(2026, 10, 20, 23, 59, 59)),
+            utc(local(2026, 10, 18, 8, 15, 0)),
+        );
+        let tasks: Vec<ExportedTask> = serde_json::from_str(&json).unwrap();
+        let items: Vec<TodoItem> = tasks.into_iter().map(ExportedTask::into_item).collect();
+
+        assert_eq!(items[0].text, "Deploy server");
+        assert_eq!(items[0].priority, 2);
+        assert_eq!(items[0].project.as_deref(), Some("ops"));
+        assert_eq!(items[0].tags, vec!["work".to_string()]);
+        assert_eq!(
+            items[0].due,
+            Some(Due {
+                date: NaiveDate::from_ymd_opt(2026, 10, 20).unwrap(),
+                time: None,
+            })
+        );
+        assert_eq!(items[0].urgency, Some(9.2));
+        assert!(items[1].done);
+        assert_eq!(items[1].completed_at, Some(local(2026, 10, 18, 8, 15, 0)));
+    }
+
+    #[test]
+    fn modify_only_sends_what_changed() {
+        let prev = TodoItem {
+            text: "Deploy".into(),
+            priority: 2,
+            project: Some("ops".into()),
+            tags: vec!["work".into(), "old".into()],
+            ..Default::default()
+        };
+        let mut item = prev.clone();
+        item.priority = 3; // still H
+        item.project = None;
+        item.tags = vec!["work".into(), "new".into()];
+
+        assert_eq!(
+            attribute_changes(Some(&prev), &item),
+            vec!["project:", "+new", "-old"]
+        );
+    }
+
+    #[test]
+    fn new_tasks_only_send_set_fields() {
+        let item = TodoItem {
+            text: "Deploy".into(),
+            priority: 7,
+            due: Some(Due {
+                date: NaiveDate::from_ymd_opt(2026, 10, 20).unwrap(),
+                time: None,
+            }),
+            tags: vec!["work".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            attribute_changes(None, &item),
+            vec!["priority:L", "due:2026-10-20T23:59:59", "+work"]
+        );
+    }
+
+    #[test]
+    fn new_tasks_are_imported_under_their_own_uuid() {
+        let backend = TaskwarriorBackend::new(PathBuf::from("/nonexistent"));
+        let mut items = vec![TodoItem {
+            text: "Deploy".into(),
+            priority: 2,
+            ..Default::default()
+        }];
+        let calls = backend.plan(&mut items);
+        let uuid = items[0].external_id.clone().unwrap();
+        assert!(is_uuid(&uuid));
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].args, ["import", "-"]);
+        let task: serde_json::Value =
+            serde_json::from_str(calls[0].input.as_deref().unwrap()).unwrap();
+        assert_eq!(task["uuid"], uuid.as_str());
+        assert_eq!(task["priority"], "H");
+
+        // Nothing left to do until the list changes again.
+        assert!(backend.plan(&mut items).is_empty());
+        items.clear();
+        assert_eq!(
+            backend.plan(&mut items),
+            [Call::new([uuid.as_str(), "delete"])]
+        );
+    }
+
+    #[test]
+    fn undoing_a_delete_sets_the_task_pending_again() {
+        let backend = TaskwarriorBackend::new(PathBuf::from("/nonexistent"));
+        let mut items = vec![TodoItem {
+            text: "Deploy".into(),
+            ..Default::default()
+        }];
+        backend.plan(&mut items);
+        let uuid = items[0].external_id.clone().unwrap();
+        let snapshot = items.clone();
+
+        let mut deleted = Vec::new();
+        assert_eq!(
+            backend.plan(&mut deleted),
+            [Call::new([uuid.as_str(), "delete"])]
+        );
+
+        let mut restored = snapshot;
+        assert_eq!(
+            backend.plan(&mut restored),
+            [Call::new([
+                uuid.as_str(),
+                "modify",
+                "status:pending",
+                "--",
+                "Deploy"
+            ])]
+        );
+        assert!(backend.plan(&mut restored).is_empty());
+    }
+
+    /// Needs the `task` binary: `cargo test -- --ignored`.
+    #[test]
+    #[ignore]
+    fn round_trip_through_a_local_taskdata() {
+        let dir = std::env::temp_dir().join(format!("zenith-tw-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let rc = dir.join("taskrc");
+        std::fs::write(&rc, "").unwrap();
+
+        let mut backend = TaskwarriorBackend::new(dir.clone());
+        backend.cmd.taskrc = Some(rc);
+        assert!(backend.load().unwrap().is_empty());
+
+        let mut items = vec![
+            TodoItem {
+                text: "Low".into(),
+                priority: 8,
+                ..Default::default()
+            },
+            TodoItem {
+                text: "Urgent".into(),
+                priority: 1,
+                tags: vec!["work".into()],
+                ..Default::default()
+            },
+        ];
+        backend.save(&mut items).unwrap();
+        assert!(items.iter().all(|t| t.external_id.is_some()));
+
+        let mut loaded = backend.load().unwrap();
+        assert_eq!(loaded[0].text, "Urgent");
+        assert_eq!(loaded[1].text, "Low");
+
+        loaded[1].done = true;
+        loaded[0].text = "Very urgent".into();
+        backend.save(&mut loaded).unwrap();
+
+        let reloaded = backend.load().unwrap();
+        assert_eq!(reloaded[0].text, "Very urgent");
+        assert!(reloaded[1].done);
+
+        loaded.remove(0);
+        backend.save(&mut loaded).unwrap();
+        assert_eq!(backend.load().unwrap().len(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,239 @@
+//! Time tracking on tasks: start/stop entries, per-period totals for the
+//! Time page and the CSV export.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use super::TodoItem;
+
+/// One stretch of work on a task. `end` is `None` while the timer runs.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub start: NaiveDateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<NaiveDateTime>,
+}
+
+impl TimeEntry {
+    /// Time spent inside `[from, to)`, counting a running entry up to `now`.
+    pub fn overlap(&self, from: NaiveDateTime, to: NaiveDateTime, now: NaiveDateTime) -> Duration {
+        let end = self.end.unwrap_or(now).min(to);
+        let start = self.start.max(from);
+        (end - start).max(Duration::zero())
+    }
+}
+
+impl TodoItem {
+    /// Start of the running timer, if any.
+    pub fn timer_started(&self) -> Option<NaiveDateTime> {
+        self.time_entries
+            .iter()
+            .find(|e| e.end.is_none())
+            .map(|e| e.start)
+    }
+
+    /// Close the running entry, if any. Returns whether one was running.
+    pub fn stop_timer(&mut self, now: NaiveDateTime) -> bool {
+        match self.time_entries.iter_mut().find(|e| e.end.is_none()) {
+            Some(entry) => {
+                entry.end = Some(now.max(entry.start));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Everything tracked on this task so far.
+    pub fn tracked(&self, now: NaiveDateTime) -> Duration {
+        self.time_entries
+            .iter()
+            .map(|e| e.end.unwrap_or(now) - e.start)
+            .fold(Duration::zero(), |a, b| a + b.max(Duration::zero()))
+    }
+}
+
+/// Period covered by a summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Today,
+    ThisWeek,
+}
+
+impl Period {
+    /// `[start, end)` of the period containing `now`; weeks start on Monday.
+    pub fn bounds(self, now: NaiveDateTime) -> (NaiveDateTime, NaiveDateTime) {
+        let today = now.date();
+        let (first, days) = match self {
+            Period::Today => (today, 1),
+            Period::ThisWeek => (
+                today - Duration::days(today.weekday().num_days_from_monday() as i64),
+                7,
+            ),
+        };
+        let start = first.and_hms_opt(0, 0, 0).unwrap_or_default();
+        (start, start + Duration::days(days))
+    }
+}
+
+/// Per-task totals within `period`, largest first, skipping tasks with no
+/// time in it.
+pub fn summary<'a>(
+    items: impl IntoIterator<Item = &'a TodoItem>,
+    period: Period,
+    now: NaiveDateTime,
+) -> Vec<(&'a TodoItem, Duration)> {
+    let (from, to) = period.bounds(now);
+    let mut totals: Vec<(&TodoItem, Duration)> = items
+        .into_iter()
+        .map(|t| {
+            let spent = t
+                .time_entries
+                .iter()
+                .map(|e| e.overlap(from, to, now))
+                .fold(Duration::zero(), |a, b| a + b);
+            (t, spent)
+        })
+        .filter(|(_, d)| *d >= Duration::minutes(1))
+        .collect();
+    totals.sort_by_key(|(_, spent)| std::cmp::Reverse(*spent));
+    totals
+}
+
+/// "1h 05m", or "12m" under an hour.
+pub fn format_duration(d: Duration) -> String {
+    let minutes = d.num_minutes().max(0);
+    match minutes / 60 {
+        0 => format!("{}m", minutes),
+        h => format!("{}h {:02}m", h, minutes % 60),
+    }
+}
+
+/// "0:12:34", for the running timer on the bar.
+pub fn format_clock(d: Duration) -> String {
+    let secs = d.num_seconds().max(0);
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Every finished entry as CSV, oldest first:
+/// `date,start,end,minutes,task,project,tags`.
+pub fn to_csv<'a>(items: impl IntoIterator<Item = &'a TodoItem>) -> String {
+    let mut rows: Vec<(NaiveDateTime, NaiveDateTime, &TodoItem)> = items
+        .into_iter()
+        .flat_map(|t| {
+            t.time_entries
+                .iter()
+                .filter_map(move |e| Some((e.start, e.end?, t)))
+        })
+        .collect();
+    rows.sort_by_key(|(start, _, _)| *start);
+
+    let mut out = String::from("date,start,end,minutes,task,project,tags\n");
+    for (start, end, item) in rows {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            start.format("%Y-%m-%d"),
+            start.format("%H:%M"),
+            end.format("%H:%M"),
+            (end - start).num_minutes(),
+            csv_field(&item.text),
+            csv_field(item.project.as_deref().unwrap_or("")),
+            csv_field(&item.tags.join(" ")),
+        ));
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// File name for an export made on `date`.
+pub fn export_name(date: NaiveDate) -> String {
+    format!("zenith-time-{}.csv", date.format("%Y-%m-%d"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wednesday 14 Oct 2026 at `h:m`.
+    fn at(h: u32, m: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 14)
+            .unwrap()
+            .and_hms_opt(h, m, 0)
+            .unwrap()
+    }
+
+    fn entry(start: NaiveDateTime, end: Option<NaiveDateTime>) -> TimeEntry {
+        TimeEntry { start, end }
+    }
+
+    #[test]
+    fn overlap_clips_to_the_window() {
+        let e = entry(at(9, 0), Some(at(11, 0)));
+        assert_eq!(
+            e.overlap(at(10, 0), at(12, 0), at(12, 0)),
+            Duration::hours(1)
+        );
+        assert_eq!(e.overlap(at(11, 0), at(12, 0), at(12, 0)), Duration::zero());
+        // A running entry counts up to now.
+        let running = entry(at(9, 0), None);
+        assert_eq!(
+            running.overlap(at(0, 0), at(23, 0), at(9, 30)),
+            Duration::minutes(30)
+        );
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        let (start, end) = Period::ThisWeek.bounds(at(15, 0));
+        assert_eq!(
+            start,
+            NaiveDate::from_ymd_opt(2026, 10, 12)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        );
+        assert_eq!(end - start, Duration::days(7));
+        let (start, end) = Period::Today.bounds(at(15, 0));
+        assert_eq!((start, end - start), (at(0, 0), Duration::days(1)));
+    }
+
+    #[test]
+    fn summary_sorts_and_skips_idle_tasks() {
+        let item = |text: &str, entries: Vec<TimeEntry>| TodoItem {
+            text: text.into(),
+            time_entries: entries,
+            ..Default::default()
+        };
+        let items = vec![
+            item("Short", vec![entry(at(9, 0), Some(at(9, 20)))]),
+            item("Long", vec![entry(at(10, 0), Some(at(11, 5)))]),
+            item("Blip", vec![entry(at(12, 0), Some(at(12, 0)))]),
+        ];
+        let totals = summary(&items, Period::Today, at(13, 0));
+        let names: Vec<&str> = totals.iter().map(|(t, _)| t.text.as_str()).collect();
+        assert_eq!(names, ["Long", "Short"]);
+        assert_eq!(format_duration(totals[0].1), "1h 05m");
+    }
+
+    #[test]
+    fn csv_quotes_awkward_fields() {
+        let items = vec![TodoItem {
+            text: "Call \"Bob\", re: invoice".into(),
+            project: Some("ops".into()),
+            tags: vec!["phone".into(), "work".into()],
+            time_entries: vec![entry(at(9, 0), Some(at(9, 30))), entry(at(10, 0), None)],
+            ..Default::default()
+        }];
+        assert_eq!(
+            to_csv(&items),
+            "date,start,end,minutes,task,project,tags\n\
+             2026-10-14,09:00,09:30,30,\"Call \"\"Bob\"\", re: invoice\",ops,phone work\n"
+        );
+    }
+}
@@ -0,0 +1,573 @@
+//! Reading and writing the [todo.txt](https://github.com/todotxt/todo.txt) format.
+//!
+//! Zenith's fields map onto the spec as follows:
+//!
+//! - priority 1–9 ↔ `(A)`–`(I)`; letters past `I` read as 9
+//! - completion ↔ a leading `x` plus optional completion and creation dates
+//! - project ↔ the first `+project`; further ones stay in the text
+//! - tags ↔ `@context`
+//! - due, recurrence and reminder ↔ `due:`, `rec:` and `remind:`
+//! - the bar's pinned focus task ↔ `focus:1`
+//! - the CalDAV UID of a synced task ↔ `uid:`, with `%`, `:` and whitespace
+//!   percent-encoded
+//! - Zenith's own task id ↔ `id:`
+//! - the time of day of the creation and completion dates ↔ `created:` and
+//!   `completed:`, written only when the leading dates cannot carry it
+//! - every completion of a recurring task ↔ one `done:` each
+//! - pomodoros finished on the task ↔ `pomo:`
+//! - tracked time ↔ one `time:start/end` each, with an empty end while the
+//!   timer runs
+//! - subtasks ↔ one `sub:` (open) or `sub-done:` each, percent-encoded like
+//!   `uid:`
+//!
+//! Any other `key:value` pair is kept verbatim in [`TodoItem::extras`] and
+//! written back at the end of the line.
+//!
+//! Only the fields are kept, not where they stood: a saved line has the
+//! text first and the tokens after it in the order above, so
+//! `Call +ops Bob due:2026-10-20 about it` comes back as
+//! `Call Bob about it +ops due:2026-10-20`.
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+
+use super::timetrack::TimeEntry;
+use super::{Due, Recurrence, Subtask, TodoItem};
+
+const DATE: &str = "%Y-%m-%d";
+const DATE_TIME: &str = "%Y-%m-%dT%H:%M";
+/// Timestamps Zenith records itself, down to the second.
+const STAMP: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Parse a whole todo.txt file; blank lines are skipped.
+pub fn parse(contents: &str) -> Vec<TodoItem> {
+    contents.lines().filter_map(parse_line).collect()
+}
+
+/// Render items as a todo.txt file, one task per line.
+pub fn serialize(items: &[TodoItem]) -> String {
+    items.iter().map(|t| format_line(t) + "\n").collect()
+}
+
+/// Parse one line. Returns `None` for blank lines.
+pub fn parse_line(line: &str) -> Option<TodoItem> {
+    let mut tokens = line.split_whitespace().peekable();
+    tokens.peek()?;
+
+    let mut item = TodoItem::default();
+
+    if tokens.next_if_eq(&"x").is_some() {
+        item.done = true;
+        if let Some(done) = tokens.next_if(|t| parse_date(t).is_some()) {
+            item.completed_at = parse_date(done).and_then(|d| d.and_hms_opt(0, 0, 0));
+        }
+    } else if let Some(p) = tokens.next_if(|t| priority_from_token(t).is_some()) {
+        item.priority = priority_from_token(p).unwrap_or(0);
+    }
+    if let Some(created) = tokens.next_if(|t| parse_date(t).is_some()) {
+        item.created = parse_date(created).and_then(|d| d.and_hms_opt(0, 0, 0));
+    }
+
+    let mut words = Vec::new();
+    let mut rec = None;
+    for token in tokens {
+        if let Some(p) = token.strip_prefix('+').filter(|p| !p.is_empty()) {
+            if item.project.is_none() {
+                item.project = Some(p.to_string());
+                continue;
+            }
+        } else if let Some(c) = token.strip_prefix('@').filter(|c| !c.is_empty()) {
+            item.tags.push(c.to_string());
+            continue;
+        } else if let Some((key, value)) = split_extra(token) {
+            let known = match key {
+                "due" => parse_due(value).map(|d| item.due = Some(d)),
+                "remind" => NaiveDateTime::parse_from_str(value, DATE_TIME)
+                    .ok()
+                    .map(|at| item.remind = Some(at)),
+                "pri" if item.done => letter_priority(value).map(|p| item.priority = p),
+                "rec" => {
+                    rec = Some(value);
+                    Some(())
+                }
+                "focus" if value == "1" => {
+                    item.focus = true;
+                    Some(())
+                }
+                "uid" => {
+                    item.external_id = Some(decode(value));
+                    Some(())
+                }
+                "id" => value.parse().ok().map(|id| item.id = id),
+                "created" => parse_stamp(value).map(|at| item.created = Some(at)),
+                "completed" => parse_stamp(value).map(|at| item.completed_at = Some(at)),
+                "done" => parse_stamp(value).map(|at| item.completions.push(at)),
+                "pomo" => value.parse().ok().map(|n| item.pomodoros = n),
+                "time" => parse_time_entry(value).map(|e| item.time_entries.push(e)),
+                "sub" | "sub-done" => {
+                    item.subtasks.push(Subtask {
+                        text: decode(value),
+                        done: key == "sub-done",
+                    });
+                    Some(())
+                }
+                _ => None,
+            };
+            if known.is_some() {
+                continue;
+            }
+            if !value.contains(':') {
+                item.extras.push((key.to_string(), value.to_string()));
+                continue;
+            }
+        }
+        words.push(token);
+    }
+    item.text = words.join(" ");
+
+    // Weekly and monthly rules take their day from the due date, so they can
+    // only be resolved once the whole line has been read.
+    if let Some(value) = rec {
+        match parse_rec(value, item.due) {
+            Some(r) => item.recurrence = Some(r),
+            None => item.extras.push(("rec".to_string(), value.to_string())),
+        }
+    }
+
+    Some(item)
+}
+
+/// Render one item as a todo.txt line.
+pub fn format_line(item: &TodoItem) -> String {
+    let mut parts: Vec<String> = Vec::new();
+
+    if item.done {
+        parts.push("x".to_string());
+        // A creation date is only unambiguous after a completion date.
+        if let Some(done) = item.completed_at {
+            parts.push(done.format(DATE).to_string());
+            if let Some(created) = item.created {
+                parts.push(created.format(DATE).to_string());
+            }
+        }
+    } else {
+        if let Some(letter) = priority_letter(item.priority) {
+            parts.push(format!("({})", letter));
+        }
+        if let Some(created) = item.created {
+            parts.push(created.format(DATE).to_string());
+        }
+    }
+
+    if !item.text.is_empty() {
+        parts.push(item.text.clone());
+    }
+    if let Some(ref p) = item.project {
+        parts.push(format!("+{}", p));
+    }
+    parts.extend(item.tags.iter().map(|t| format!("@{}", t)));
+
+    if let Some(due) = item.due {
+        let value = match due.time {
+            Some(t) => due.date.and_time(t).format(DATE_TIME).to_string(),
+            None => due.date.format(DATE).to_string(),
+        };
+        parts.push(format!("due:{}", value));
+    }
+    if let Some(rec) = item.recurrence {
+        parts.push(format!("rec:{}", format_rec(rec)));
+    }
+    if let Some(at) = item.remind {
+        parts.push(format!("remind:{}", at.format(DATE_TIME)));
+    }
+    if item.focus {
+        parts.push("focus:1".to_string());
+    }
+    if let Some(ref uid) = item.external_id {
+        parts.push(format!("uid:{}", encode(uid)));
+    }
+    if item.done {
+        if let Some(letter) = priority_letter(item.priority) {
+            parts.push(format!("pri:{}", letter));
+        }
+    }
+    if item.id != 0 {
+        parts.push(format!("id:{}", item.id));
+    }
+    // The leading dates only hold the day, and a done task only has room
+    // for a creation date after a completion date.
+    let completed = item.completed_at.filter(|_| item.done);
+    if let Some(created) = item.created {
+        if has_time(created) || (item.done && completed.is_none()) {
+            parts.push(format!("created:{}", created.format(STAMP)));
+        }
+    }
+    if let Some(done) = completed.filter(|&at| has_time(at)) {
+        parts.push(format!("completed:{}", done.format(STAMP)));
+    }
+    parts.extend(
+        item.completions
+            .iter()
+            .map(|at| format!("done:{}", at.format(STAMP))),
+    );
+    if item.pomodoros > 0 {
+        parts.push(format!("pomo:{}", item.pomodoros));
+    }
+    parts.extend(item.time_entries.iter().map(|e| {
+        let end = e.end.map(|at| at.format(STAMP).to_string());
+        format!("time:{}/{}", e.start.format(STAMP), end.unwrap_or_default())
+    }));
+    parts.extend(item.subtasks.iter().map(|sub| {
+        let key = if sub.done { "sub-done" } else { "sub" };
+        format!("{}:{}", key, encode(&sub.text))
+    }));
+    parts.extend(item.extras.iter().map(|(k, v)| format!("{}:{}", k, v)));
+
+    parts.join(" ")
+}
+
+/// Percent-encode the characters that would split or confuse a `uid:` or
+/// `sub:` token.
+fn encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c == '%' || c == ':' || c.is_whitespace() {
+            let mut buf = [0; 4];
+            for b in c.encode_utf8(&mut buf).bytes() {
+                out.push_str(&format!("%{:02X}", b));
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match hex {
+            Some(b) => {
+                out.push(b);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s, DATE).ok()
+}
+
+fn parse_stamp(s: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(s, STAMP).ok()
+}
+
+fn has_time(at: NaiveDateTime) -> bool {
+    at.time() != NaiveTime::MIN
+}
+
+/// `start/end`, or `start/` for a running timer.
+fn parse_time_entry(s: &str) -> Option<TimeEntry> {
+    let (start, end) = s.split_once('/')?;
+    let end = match end {
+        "" => None,
+        end => Some(parse_stamp(end)?),
+    };
+    Some(TimeEntry {
+        start: parse_stamp(start)?,
+        end,
+    })
+}
+
+fn parse_due(s: &str) -> Option<Due> {
+    if let Ok(at) = NaiveDateTime::parse_from_str(s, DATE_TIME) {
+        return Some(Due {
+            date: at.date(),
+            time: Some(at.time()),
+        });
+    }
+    parse_date(s).map(|date| Due { date, time: None })
+}
+
+/// `key:value` where the key starts with a letter, so times ("10:30") stay
+/// part of the text. Unknown keys additionally need a colon-free value,
+/// which keeps URLs in the text too.
+fn split_extra(token: &str) -> Option<(&str, &str)> {
+    let (key, value) = token.split_once(':')?;
+    let key_ok = key.starts_with(|c: char| c.is_ascii_alphabetic())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    (key_ok && !value.is_empty()).then_some((key, value))
+}
+
+/// "(A)" → 1. Letters after `I` are clamped to the lowest priority.
+fn priority_from_token(token: &str) -> Option<u8> {
+    let letter = token.strip_prefix('(')?.strip_suffix(')')?;
+    letter_priority(letter)
+}
+
+fn letter_priority(letter: &str) -> Option<u8> {
+    match letter.as_bytes() {
+        [c @ b'A'..=b'Z'] => Some((c - b'A' + 1).min(9)),
+        _ => None,
+    }
+}
+
+fn priority_letter(priority: u8) -> Option<char> {
+    (1..=9)
+        .contains(&priority)
+        .then(|| (b'A' + priority - 1) as char)
+}
+
+/// `rec:` values as used by common todo.txt clients: a leading `+` counts
+/// from the due date, otherwise from completion; units are d(ays), b(usiness
+/// days), w(eeks) and m(onths).
+fn parse_rec(value: &str, due: Option<Due>) -> Option<Recurrence> {
+    let (strict, body) = match value.strip_prefix('+') {
+        Some(rest) => (true, rest),
+        None => (false, value),
+    };
+    let unit = body.chars().last()?;
+    let n: u32 = body[..body.len() - unit.len_utf8()].parse().ok()?;
+
+    match (strict, n, unit) {
+        (true, 1, 'd') => Some(Recurrence::Daily),
+        (true, n, 'd') if n > 1 => Some(Recurrence::EveryDays(n)),
+        (false, n, 'd') if n > 0 => Some(Recurrence::AfterCompletion(n)),
+        (true, 1, 'b') => Some(Recurrence::Weekdays),
+        (true, 1, 'w') => due.map(|d| Recurrence::Weekly(d.date.weekday())),
+        (true, 1, 'm') => due.map(|d| Recurrence::Monthly(d.date.day())),
+        _ => None,
+    }
+}
+
+fn format_rec(rec: Recurrence) -> String {
+    match rec {
+        Recurrence::Daily => "+1d".to_string(),
+        Recurrence::Weekdays => "+1b".to_string(),
+        Recurrence::Weekly(_) => "+1w".to_string(),
+        Recurrence::Monthly(_) => "+1m".to_string(),
+        Recurrence::EveryDays(n) => format!("+{}d", n.max(1)),
+        Recurrence::AfterCompletion(n) => format!("{}d", n.max(1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Weekday;
+
+    fn round_trip(line: &str) -> String {
+        format_line(&parse_line(line).expect("non-empty line"))
+    }
+
+    #[test]
+    fn canonical_lines_round_trip_unchanged() {
+        let lines = [
+            "Call mom",
+            "(A) Thank Mom for the meatballs @phone",
+            "(B) 2026-10-01 Schedule Goodwill pickup +GarageSale @phone",
+            "x 2026-10-18 2026-10-01 Post signs around the neighborhood +GarageSale",
+            "x Download Todo.txt mobile app @phone",
+            "(C) Pay rent due:2026-11-01 rec:+1m",
+            "Standup due:2026-10-19T09:30 rec:+1b remind:2026-10-19T09:15",
+            "Water plants due:2026-10-20 rec:3d",
+            "x 2026-10-18 Ship release pri:B",
+            "Read https://example.com/a:b at 10:30 +reading",
+        ];
+        for line in lines {
+            assert_eq!(round_trip(line), line);
+        }
+    }
+
+    #[test]
+    fn tokens_inside_the_text_move_to_the_end() {
+        let line = "Call +ops @phone Bob due:2026-10-20 about it h:1";
+        let item = parse_line(line).unwrap();
+        assert_eq!(item.text, "Call Bob about it");
+        assert_eq!(
+            format_line(&item),
+            "Call Bob about it +ops @phone due:2026-10-20 h:1"
+        );
+        // After that first save the line is stable.
+        assert_eq!(round_trip(&format_line(&item)), format_line(&item));
+    }
+
+    #[test]
+    fn unknown_key_values_are_preserved() {
+        let line = "(D) Review PR +zenith @work due:2026-10-20 t:2026-10-19 h:1 pr:4711";
+        let item = parse_line(line).unwrap();
+        assert_eq!(
+            item.extras,
+            vec![
+                ("t".to_string(), "2026-10-19".to_string()),
+                ("h".to_string(), "1".to_string()),
+                ("pr".to_string(), "4711".to_string()),
+            ]
+        );
+        assert_eq!(format_line(&item), line);
+    }
+
+    #[test]
+    fn unsupported_recurrence_and_bad_dates_are_kept_verbatim() {
+        let item = parse_line("Renew passport rec:+10y due:soon").unwrap();
+        assert_eq!(item.recurrence, None);
+        assert_eq!(item.due, None);
+        assert_eq!(
+            round_trip("Renew passport due:soon rec:+10y"),
+            "Renew passport due:soon rec:+10y"
+        );
+    }
+
+    #[test]
+    fn extra_projects_stay_in_the_text() {
+        let item = parse_line("Plan trip +travel +budget").unwrap();
+        assert_eq!(item.project.as_deref(), Some("travel"));
+        assert_eq!(item.text, "Plan trip +budget");
+    }
+
+    #[test]
+    fn priorities_map_onto_letters() {
+        assert_eq!(parse_line("(A) a").unwrap().priority, 1);
+        assert_eq!(parse_line("(I) a").unwrap().priority, 9);
+        assert_eq!(parse_line("(Z) a").unwrap().priority, 9);
+        assert_eq!(parse_line("(a) a").unwrap().priority, 0);
+
+        let item = TodoItem {
+            text: "a".into(),
+            priority: 3,
+            ..Default::default()
+        };
+        assert_eq!(format_line(&item), "(C) a");
+    }
+
+    #[test]
+    fn completion_and_creation_dates() {
+        let item = parse_line("x 2026-10-18 2026-10-01 Done thing").unwrap();
+        assert!(item.done);
+        assert_eq!(
+            item.completed_at.map(|d| d.date()),
+            parse_date("2026-10-18")
+        );
+        assert_eq!(item.created.map(|d| d.date()), parse_date("2026-10-01"));
+        assert_eq!(item.text, "Done thing");
+    }
+
+    #[test]
+    fn recurrence_takes_its_day_from_the_due_date() {
+        let item = parse_line("Gym due:2026-10-19 rec:+1w").unwrap();
+        assert_eq!(item.recurrence, Some(Recurrence::Weekly(Weekday::Mon)));
+        let item = parse_line("Rent due:2026-11-15 rec:+1m").unwrap();
+        assert_eq!(item.recurrence, Some(Recurrence::Monthly(15)));
+    }
+
+    #[test]
+    fn caldav_uids_survive_a_save() {
+        let item = TodoItem {
+            text: "Pay rent".into(),
+            external_id: Some("4f1c-9a: 50%@example.com".into()),
+            ..Default::default()
+        };
+        let line = format_line(&item);
+        assert_eq!(line, "Pay rent uid:4f1c-9a%3A%2050%25@example.com");
+        let back = parse_line(&line).unwrap();
+        assert_eq!(back.external_id, item.external_id);
+        assert_eq!(back.text, "Pay rent");
+        assert!(back.extras.is_empty());
+    }
+
+    #[test]
+    fn fields_from_other_features_survive_a_save() {
+        let at = |s: &str| parse_stamp(s).unwrap();
+        let item = TodoItem {
+            id: 42,
+            text: "Water plants".into(),
+            done: true,
+            recurrence: Some(Recurrence::AfterCompletion(3)),
+            completions: vec![at("2026-10-12T08:05:00"), at("2026-10-15T19:40:12")],
+            completed_at: Some(at("2026-10-18T09:30:00")),
+            created: Some(at("2026-10-01T07:45:30")),
+            pomodoros: 3,
+            time_entries: vec![
+                TimeEntry {
+                    start: at("2026-10-18T09:00:00"),
+                    end: Some(at("2026-10-18T09:25:00")),
+                },
+                TimeEntry {
+                    start: at("2026-10-18T10:00:00"),
+                    end: None,
+                },
+            ],
+            subtasks: vec![
+                Subtask {
+                    text: "Fern: 50%".into(),
+                    done: true,
+                },
+                Subtask {
+                    text: "Cactus".into(),
+                    done: false,
+                },
+            ],
+            ..Default::default()
+        };
+        let line = format_line(&item);
+        assert_eq!(
+            line,
+            "x 2026-10-18 2026-10-01 Water plants rec:3d id:42 \
+             created:2026-10-01T07:45:30 completed:2026-10-18T09:30:00 \
+             done:2026-10-12T08:05:00 done:2026-10-15T19:40:12 pomo:3 \
+             time:2026-10-18T09:00:00/2026-10-18T09:25:00 time:2026-10-18T10:00:00/ \
+             sub-done:Fern%3A%2050%25 sub:Cactus"
+        );
+
+        let back = parse_line(&line).unwrap();
+        assert_eq!(back.id, item.id);
+        assert_eq!(back.text, item.text);
+        assert_eq!(back.recurrence, item.recurrence);
+        assert_eq!(back.completions, item.completions);
+        assert_eq!(back.completed_at, item.completed_at);
+        assert_eq!(back.created, item.created);
+        assert_eq!(back.pomodoros, item.pomodoros);
+        assert_eq!(back.time_entries, item.time_entries);
+        let subs: Vec<_> = back.subtasks.iter().map(|s| (&*s.text, s.done)).collect();
+        assert_eq!(subs, [("Fern: 50%", true), ("Cactus", false)]);
+        assert!(back.extras.is_empty());
+        assert_eq!(format_line(&back), line);
+    }
+
+    #[test]
+    fn creation_time_is_kept_without_a_completion_date() {
+        let item = TodoItem {
+            text: "Old".into(),
+            done: true,
+            created: parse_date("2026-10-01").and_then(|d| d.and_hms_opt(0, 0, 0)),
+            ..Default::default()
+        };
+        let line = format_line(&item);
+        assert_eq!(line, "x Old created:2026-10-01T00:00:00");
+        assert_eq!(parse_line(&line).unwrap().created, item.created);
+    }
+
+    #[test]
+    fn whole_file_round_trip() {
+        let file = "(A) First @home\n\nx 2026-10-18 Second\nThird key:value\n";
+        let items = parse(file);
+        assert_eq!(items.len(), 3);
+        assert_eq!(
+            serialize(&items),
+            "(A) First @home\nx 2026-10-18 Second\nThird key:value\n"
+        );
+    }
+}
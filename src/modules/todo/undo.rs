@@ -0,0 +1,150 @@
+use super::TodoItem;
+
+/// How many steps of history to keep.
+const MAX_STEPS: usize = 50;
+
+/// Snapshot-based undo/redo over the task list.
+///
+/// Every mutation records the list as it was beforehand, so each undo step
+/// restores exactly one add, delete, toggle, reorder or edit. The archive
+/// file is not part of the snapshots, so steps that move tasks in or out of
+/// it carry an [`ArchiveMove`] for undo and redo to replay.
+#[derive(Debug, Clone, Default)]
+pub struct UndoStack {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+}
+
+#[derive(Debug, Clone)]
+struct Step {
+    items: Vec<TodoItem>,
+    archive: ArchiveMove,
+}
+
+/// Tasks one step moved into the archive file or took back out of it.
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveMove {
+    pub archived: Vec<TodoItem>,
+    pub restored: Vec<TodoItem>,
+}
+
+impl ArchiveMove {
+    pub fn is_empty(&self) -> bool {
+        self.archived.is_empty() && self.restored.is_empty()
+    }
+
+    /// The move that puts the archive back the way it was.
+    fn reversed(&self) -> Self {
+        Self {
+            archived: self.restored.clone(),
+            restored: self.archived.clone(),
+        }
+    }
+}
+
+impl UndoStack {
+    /// Remember `items` as the state to return to. Clears the redo branch.
+    pub fn record(&mut self, items: &[TodoItem]) {
+        self.undo.push(Step {
+            items: items.to_vec(),
+            archive: ArchiveMove::default(),
+        });
+        if self.undo.len() > MAX_STEPS {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Note that the step just recorded also changed the archive.
+    pub fn record_archive(&mut self, change: ArchiveMove) {
+        if let Some(step) = self.undo.last_mut() {
+            step.archive = change;
+        }
+    }
+
+    /// Apply a change made outside the history (e.g. one pulled in by sync)
+    /// to every snapshot, so neither undo nor redo takes it back.
+    pub fn rebase(&mut self, mut change: impl FnMut(&mut Vec<TodoItem>)) {
+        for step in self.undo.iter_mut().chain(self.redo.iter_mut()) {
+            change(&mut step.items);
+        }
+    }
+
+    /// Step back, swapping `items` with the previous snapshot. Returns the
+    /// change to make to the archive, or `None` with nothing to undo.
+    pub fn undo(&mut self, items: &mut Vec<TodoItem>) -> Option<ArchiveMove> {
+        let prev = self.undo.pop()?;
+        let change = prev.archive.reversed();
+        self.redo.push(Step {
+            items: std::mem::replace(items, prev.items),
+            archive: prev.archive,
+        });
+        Some(change)
+    }
+
+    /// Re-apply the most recently undone step, like [`UndoStack::undo`].
+    pub fn redo(&mut self, items: &mut Vec<TodoItem>) -> Option<ArchiveMove> {
+        let next = self.redo.pop()?;
+        let change = next.archive.clone();
+        self.undo.push(Step {
+            items: std::mem::replace(items, next.items),
+            archive: next.archive,
+        });
+        Some(change)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: u64) -> TodoItem {
+        TodoItem {
+            id,
+            done: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn archive_moves_replay_with_their_step() {
+        let mut history = UndoStack::default();
+        let mut items = vec![item(1), item(2)];
+        history.record(&items);
+        history.record_archive(ArchiveMove {
+            archived: vec![item(2)],
+            restored: Vec::new(),
+        });
+        items.pop();
+
+        let undo = history.undo(&mut items).unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(undo.archived.is_empty());
+        assert_eq!(undo.restored.len(), 1);
+
+        let redo = history.redo(&mut items).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(redo.archived.len(), 1);
+        assert!(history.redo(&mut items).is_none());
+    }
+
+    #[test]
+    fn rebased_changes_survive_undo_and_redo() {
+        let mut history = UndoStack::default();
+        let mut items = vec![item(1)];
+        history.record(&items);
+        items.push(item(2));
+        history.record(&items);
+        items.push(item(3));
+        history.undo(&mut items);
+
+        items.push(item(9));
+        history.rebase(|snapshot| snapshot.push(item(9)));
+
+        history.undo(&mut items);
+        assert_eq!(items.iter().map(|t| t.id).collect::<Vec<_>>(), [1, 9]);
+        history.redo(&mut items);
+        history.redo(&mut items);
+        assert_eq!(items.iter().map(|t| t.id).collect::<Vec<_>>(), [1, 2, 3, 9]);
+    }
+}
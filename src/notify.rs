@@ -0,0 +1,135 @@
+use gtk4::gio;
+use gtk4::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+const BUS_NAME: &str = "org.freedesktop.Notifications";
+const OBJECT_PATH: &str = "/org/freedesktop/Notifications";
+const INTERFACE: &str = "org.freedesktop.Notifications";
+const APP_NAME: &str = "Zenith";
+
+/// Callback invoked with the action key the user clicked.
+type ActionHandler = Box<dyn Fn(&str)>;
+
+/// Thin client for the freedesktop notification service on the session bus.
+///
+/// Action buttons are routed back to the closure passed to [`Notifier::notify`]
+/// by listening for `ActionInvoked` signals.
+pub struct Notifier {
+    conn: gio::DBusConnection,
+    handlers: Rc<RefCell<HashMap<u32, ActionHandler>>>,
+    _subscriptions: [gio::SignalSubscription; 2],
+}
+
+impl Notifier {
+    /// Connect to the session bus. Returns `None` (and logs) when no bus is
+    /// available, in which case notifications are simply skipped.
+    pub fn connect() -> Option<Self> {
+        let conn = match gio::bus_get_sync(gio::BusType::Session, gio::Cancellable::NONE) {
+            Ok(c) => c,
+            Err(e) => {
+                log::warn!("Notifications disabled – no session bus: {e}");
+                return None;
+            }
+        };
+
+        let handlers: Rc<RefCell<HashMap<u32, ActionHandler>>> =
+            Rc::new(RefCell::new(HashMap::new()));
+
+        let invoked = conn.subscribe_to_signal(
+            Some(BUS_NAME),
+            Some(INTERFACE),
+            Some("ActionInvoked"),
+            Some(OBJECT_PATH),
+            None,
+            gio::DBusSignalFlags::NONE,
+            {
+                let handlers = Rc::clone(&handlers);
+                move |signal| {
+                    if let Some((id, action)) = signal.parameters.get::<(u32, String)>() {
+                        // Take the handler out first so it may post new
+                        // notifications without re-borrowing the map.
+                        let handler = handlers.borrow_mut().remove(&id);
+                        if let Some(h) = handler {
+                            h(&action);
+                        }
+                    }
+                }
+            },
+        );
+
+        let closed = conn.subscribe_to_signal(
+            Some(BUS_NAME),
+            Some(INTERFACE),
+            Some("NotificationClosed"),
+            Some(OBJECT_PATH),
+            None,
+            gio::DBusSignalFlags::NONE,
+            {
+                let handlers = Rc::clone(&handlers);
+                move |signal| {
+                    if let Some((id, _reason)) = signal.parameters.get::<(u32, u32)>() {
+                        handlers.borrow_mut().remove(&id);
+                    }
+                }
+            },
+        );
+
+        Some(Self {
+            conn,
+            handlers,
+            _subscriptions: [invoked, closed],
+        })
+    }
+
+    /// Show a notification. `actions` is a list of `(key, label)` pairs;
+    /// `on_action` receives the key of whichever button was clicked.
+    pub fn notify(
+        &self,
+        summary: &str,
+        body: &str,
+        actions: &[(&str, &str)],
+        on_action: impl Fn(&str) + 'static,
+    ) {
+        let action_list: Vec<String> = actions
+            .iter()
+            .flat_map(|(key, label)| [key.to_string(), label.to_string()])
+            .collect();
+        let hints: HashMap<String, glib::Variant> = HashMap::new();
+
+        let params = (
+            APP_NAME,
+            0u32,
+            "appointment-soon",
+            summary,
+            body,
+            action_list,
+            hints,
+            -1i32,
+        )
+            .to_variant();
+
+        let handlers = Rc::clone(&self.handlers);
+        let has_actions = !actions.is_empty();
+        self.conn.call(
+            Some(BUS_NAME),
+            OBJECT_PATH,
+            INTERFACE,
+            "Notify",
+            Some(&params),
+            Some(glib::VariantTy::new("(u)").expect("valid variant type")),
+            gio::DBusCallFlags::NONE,
+            -1,
+            gio::Cancellable::NONE,
+            move |res| match res {
+                Ok(reply) => {
+                    if let (true, Some((id,))) = (has_actions, reply.get::<(u32,)>()) {
+                        handlers.borrow_mut().insert(id, Box::new(on_action));
+                    }
+                }
+                Err(e) => log::warn!("Failed to send notification: {e}"),
+            },
+        );
+    }
+}
@@ -82,6 +82,12 @@ window {{
     color: #ff0055; /* Dawn Red */
 }}
 
+/* Clock showing another time zone */
+.zenith-clock-remote {{
+    color: #00ccff;
+    text-shadow: 0px 0px 8px rgba(0, 204, 255, 0.3);
+}}
+
 /* ── Arch Logo ─────────────────────────────────────────────────── */
 .zenith-logo {{
     font-family: "JetBrainsMono Nerd Font", monospace;
@@ -136,32 +142,110 @@ window {{
 }}
 
 /* Calendar header (month/year navigation) */
-.zenith-calendar > header {{
-    color: #ffffff;
-    font-weight: 700;
-}}
-
-.zenith-calendar > header > button {{
+.zenith-calendar-nav {{
     color: #00ccff;
     background: transparent;
     border: none;
+    box-shadow: none;
+    min-height: 0;
+    padding: 2px 8px;
 }}
 
-.zenith-calendar > header > button:hover {{
+.zenith-calendar-nav:hover {{
     background: rgba(0, 204, 255, 0.15);
     border-radius: 6px;
 }}
 
+.zenith-calendar-title {{
+    color: #ffffff;
+    font-weight: 700;
+}}
+
 /* Day cells */
-.zenith-calendar :selected {{
+.zenith-calendar-weekday {{
+    color: #8b949e;
+    font-size: 11px;
+    font-weight: 700;
+    padding: 2px 0;
+}}
+
+.zenith-calendar-day {{
+    min-width: 28px;
+    min-height: 28px;
+    padding: 0;
+    border: none;
+    border-radius: 50%;
+    box-shadow: none;
+    background: transparent;
+    color: #cdd6f4;
+}}
+
+.zenith-calendar-day:hover {{
+    background: rgba(255, 255, 255, 0.08);
+}}
+
+.zenith-calendar-selected {{
+    box-shadow: inset 0 0 0 1px #00ccff;
+}}
+
+.zenith-calendar-other-month {{
+    color: #484f58;
+}}
+
+.zenith-calendar-today {{
     background-color: #7700ff;
     color: #ffffff;
-    border-radius: 50%;
+    font-weight: 700;
+}}
+
+/* ── Pomodoro ──────────────────────────────────────────────────── */
+.zenith-pomodoro-btn {{
+    background: transparent;
+    border: none;
+    box-shadow: none;
+    padding: 4px 10px;
+    min-height: 0;
+    min-width: 0;
+    font-family: "JetBrainsMono Nerd Font", "Inter", monospace;
+    font-size: 13px;
+    font-weight: 700;
 }}
 
-.zenith-calendar .day-number:hover {{
+.zenith-pomodoro-btn:hover {{
     background: rgba(255, 255, 255, 0.08);
-    border-radius: 50%;
+    border-radius: 6px;
+}}
+
+.zenith-pomodoro-work {{
+    color: #ff5555;
+    text-shadow: 0px 0px 6px rgba(255, 85, 85, 0.35);
+}}
+
+.zenith-pomodoro-break {{
+    color: #00ff99;
+    text-shadow: 0px 0px 6px rgba(0, 255, 153, 0.3);
+}}
+
+.zenith-pomodoro-paused {{
+    color: #8b949e;
+}}
+
+.zenith-pomodoro-menu {{
+    padding: 10px 12px;
+    min-width: 220px;
+}}
+
+.zenith-pomodoro-menu-title {{
+    font-family: "Inter", sans-serif;
+    font-size: 11px;
+    font-weight: 600;
+    color: #8b949e;
+}}
+
+/* ── Import / Export Menu ──────────────────────────────────────── */
+.zenith-todo-exchange-menu {{
+    padding: 8px;
+    min-width: 180px;
 }}
 
 /* ═══════════════════════════════════════════════════════════════════
@@ -243,6 +327,17 @@ window {{
     text-shadow: 0px 0px 8px rgba(255, 255, 255, 0.15);
 }}
 
+.zenith-todo-list-switch > button {{
+    font-family: "Inter", "JetBrainsMono Nerd Font", sans-serif;
+    font-size: 14px;
+    font-weight: 800;
+    color: #ffffff;
+    background: transparent;
+    border: none;
+    box-shadow: none;
+    padding: 0 4px;
+}}
+
 .zenith-todo-progress {{
     font-family: "JetBrainsMono Nerd Font", monospace;
     font-size: 11px;
@@ -253,6 +348,59 @@ window {{
     padding: 2px 8px;
 }}
 
+.zenith-todo-header-btn {{
+    background: transparent;
+    border: none;
+    box-shadow: none;
+    min-height: 0;
+    min-width: 0;
+    padding: 2px 8px;
+    border-radius: 8px;
+    font-family: "Inter", sans-serif;
+    font-size: 11px;
+    font-weight: 600;
+    color: #8b949e;
+    transition: all 150ms ease;
+}}
+
+.zenith-todo-header-btn:hover {{
+    background: rgba(0, 204, 255, 0.12);
+    color: #00ccff;
+}}
+
+/* ── Sync Indicator ────────────────────────────────────────────── */
+.zenith-todo-sync {{
+    background: transparent;
+    border: none;
+    box-shadow: none;
+    min-height: 0;
+    min-width: 0;
+    padding: 2px 6px;
+    border-radius: 8px;
+    font-size: 12px;
+    color: #8b949e;
+}}
+
+.zenith-todo-sync:hover {{
+    background: rgba(255, 255, 255, 0.06);
+}}
+
+.zenith-todo-sync-ok {{
+    color: #3fb950;
+}}
+
+.zenith-todo-sync-busy {{
+    color: #00ccff;
+}}
+
+.zenith-todo-sync-offline {{
+    color: #d29922;
+}}
+
+.zenith-todo-sync-error {{
+    color: #f85149;
+}}
+
 /* ── Progress Bar ──────────────────────────────────────────────── */
 .zenith-todo-progress-track {{
     margin: 4px 14px 2px 14px;
@@ -279,6 +427,47 @@ window {{
     background: linear-gradient(90deg, #00ff99, #00ccff);
 }}
 
+/* ── Search ────────────────────────────────────────────────────── */
+.zenith-todo-search {{
+    margin: 6px 12px 0 12px;
+    background: rgba(255, 255, 255, 0.04);
+    border: 1px solid #21262d;
+    border-radius: 8px;
+    color: #cdd6f4;
+    padding: 2px 8px;
+    min-height: 0;
+    font-family: "Inter", sans-serif;
+    font-size: 12px;
+    caret-color: #00ccff;
+}}
+
+.zenith-todo-search:focus-within {{
+    border-color: #7700ff;
+    box-shadow: 0 0 0 1px rgba(119, 0, 255, 0.3);
+}}
+
+/* ── Filter Bar ────────────────────────────────────────────────── */
+.zenith-todo-filter-bar {{
+    padding: 4px 12px 2px 12px;
+}}
+
+.zenith-todo-filter > button {{
+    background: rgba(255, 255, 255, 0.04);
+    border: 1px solid #21262d;
+    border-radius: 6px;
+    box-shadow: none;
+    min-height: 0;
+    padding: 2px 6px;
+    font-family: "Inter", sans-serif;
+    font-size: 11px;
+    color: #8b949e;
+}}
+
+.zenith-todo-filter > button:hover {{
+    color: #cdd6f4;
+    border-color: #30363d;
+}}
+
 /* ── Separator ─────────────────────────────────────────────────── */
 .zenith-todo-sep {{
     margin: 4px 14px;
@@ -310,6 +499,20 @@ window {{
     opacity: 0.5;
 }}
 
+/* Keyboard cursor */
+.zenith-todo-row:focus {{
+    outline: none;
+    background: rgba(0, 204, 255, 0.08);
+    box-shadow: inset 0 0 0 1px rgba(0, 204, 255, 0.45);
+}}
+
+.zenith-todo-empty {{
+    padding: 12px;
+    font-family: "Inter", sans-serif;
+    font-size: 12px;
+    color: #6e7681;
+}}
+
 /* ── Priority Accent Strip ─────────────────────────────────────── */
 .zenith-todo-accent {{
     border-radius: 2px;
@@ -334,6 +537,72 @@ window {{
     background: #30363d;
 }}
 
+/* ── Subtask Expander & Section ────────────────────────────────── */
+.zenith-todo-expander {{
+    background: transparent;
+    border: none;
+    box-shadow: none;
+    min-height: 0;
+    min-width: 0;
+    padding: 0 2px;
+    font-size: 10px;
+    color: #6e7681;
+}}
+
+.zenith-todo-expander:hover {{
+    color: #00ccff;
+}}
+
+.zenith-todo-subtasks {{
+    margin: 0 6px 4px 34px;
+    padding-left: 8px;
+    border-left: 1px solid #21262d;
+}}
+
+.zenith-todo-subtask-row {{
+    padding: 2px 4px;
+    border-radius: 6px;
+}}
+
+.zenith-todo-subtask-row:hover {{
+    background: rgba(255, 255, 255, 0.03);
+}}
+
+.zenith-todo-subtask-text {{
+    font-family: "Inter", sans-serif;
+    font-size: 12px;
+    color: #adb5c7;
+}}
+
+.zenith-todo-subtask-entry {{
+    background: transparent;
+    border: none;
+    border-bottom: 1px dashed #30363d;
+    border-radius: 0;
+    box-shadow: none;
+    min-height: 0;
+    padding: 2px 4px;
+    font-family: "Inter", sans-serif;
+    font-size: 12px;
+    color: #cdd6f4;
+    caret-color: #00ccff;
+}}
+
+.zenith-todo-subtask-badge {{
+    font-family: "JetBrainsMono Nerd Font", monospace;
+    font-size: 10px;
+    font-weight: 700;
+    padding: 1px 6px;
+    border-radius: 6px;
+    color: #8b949e;
+    background: rgba(255, 255, 255, 0.06);
+}}
+
+.zenith-todo-subtask-badge-done {{
+    color: #00ff99;
+    background: rgba(0, 255, 153, 0.10);
+}}
+
 /* ── Checkbox ──────────────────────────────────────────────────── */
 .zenith-todo-check {{
     min-width: 16px;
@@ -348,6 +617,19 @@ window {{
     color: #cdd6f4;
 }}
 
+.zenith-todo-edit-entry {{
+    background: rgba(255, 255, 255, 0.06);
+    border: 1px solid #7700ff;
+    border-radius: 6px;
+    box-shadow: none;
+    min-height: 0;
+    padding: 2px 6px;
+    font-family: "Inter", sans-serif;
+    font-size: 13px;
+    color: #cdd6f4;
+    caret-color: #00ccff;
+}}
+
 .zenith-todo-text-done {{
     text-decoration: line-through;
     color: #6e7681;
@@ -378,8 +660,51 @@ window {{
     background: rgba(0, 204, 255, 0.12);
 }}
 
+/* ── Project / Tag Chips ───────────────────────────────────────── */
+.zenith-todo-chip {{
+    font-family: "JetBrainsMono Nerd Font", monospace;
+    font-size: 10px;
+    font-weight: 700;
+    padding: 1px 5px;
+    border-radius: 6px;
+}}
+
+.zenith-todo-project {{
+    color: #b98aff;
+    background: rgba(119, 0, 255, 0.16);
+}}
+
+.zenith-todo-tag {{
+    color: #00ff99;
+    background: rgba(0, 255, 153, 0.10);
+}}
+
+/* ── Due Date Chip ─────────────────────────────────────────────── */
+.zenith-todo-due {{
+    font-family: "JetBrainsMono Nerd Font", monospace;
+    font-size: 10px;
+    font-weight: 700;
+    padding: 1px 6px;
+    border-radius: 6px;
+    color: #8b949e;
+    background: rgba(255, 255, 255, 0.06);
+}}
+
+.zenith-todo-due-overdue {{
+    color: #ff5555;
+    background: rgba(255, 85, 85, 0.14);
+}}
+
+.zenith-todo-remind {{
+    font-size: 11px;
+    color: #ffcc00;
+}}
+
 /* ── Action Buttons (move / delete) ────────────────────────────── */
 .zenith-todo-move-btn,
+.zenith-todo-list-move-btn > button,
+.zenith-todo-timer-btn,
+.zenith-todo-focus-btn,
 .zenith-todo-del-btn {{
     background: transparent;
     border: none;
@@ -393,7 +718,8 @@ window {{
     transition: all 150ms ease;
 }}
 
-.zenith-todo-move-btn:hover {{
+.zenith-todo-move-btn:hover,
+.zenith-todo-list-move-btn > button:hover {{
     background: rgba(0, 204, 255, 0.12);
     color: #00ccff;
 }}
@@ -403,6 +729,154 @@ window {{
     color: #ff0055;
 }}
 
+.zenith-todo-focus-btn:hover {{
+    background: rgba(255, 204, 0, 0.12);
+    color: #ffcc00;
+}}
+
+.zenith-todo-focus-btn-active {{
+    color: #ffcc00;
+}}
+
+.zenith-todo-timer-btn:hover {{
+    background: rgba(0, 255, 153, 0.12);
+    color: #00ff99;
+}}
+
+.zenith-todo-timer-btn-active {{
+    color: #00ff99;
+}}
+
+/* ── Time Tracking ─────────────────────────────────────────────── */
+.zenith-todo-btn-timing {{
+    color: #00ff99;
+    text-shadow: 0px 0px 8px rgba(0, 255, 153, 0.4);
+    animation: none;
+}}
+
+.zenith-todo-time-heading {{
+    margin: 6px 6px 2px 6px;
+    font-family: "Inter", sans-serif;
+    font-size: 11px;
+    font-weight: 700;
+    color: #8b949e;
+}}
+
+.zenith-todo-pomodoros {{
+    font-size: 11px;
+    color: #ff5555;
+}}
+
+.zenith-todo-time-amount {{
+    font-family: "JetBrainsMono Nerd Font", monospace;
+    font-size: 11px;
+    color: #00ff99;
+}}
+
+.zenith-todo-export-btn {{
+    margin: 8px 6px 4px 6px;
+}}
+
+/* ── Stats ─────────────────────────────────────────────────────── */
+.zenith-todo-stat {{
+    padding: 6px 4px;
+    border-radius: 8px;
+    background: rgba(255, 255, 255, 0.04);
+}}
+
+.zenith-todo-stat-value {{
+    font-family: "JetBrainsMono Nerd Font", monospace;
+    font-size: 15px;
+    font-weight: 700;
+    color: #ffffff;
+}}
+
+.zenith-todo-stat-alert {{
+    color: #ff0055;
+}}
+
+.zenith-todo-stat-caption {{
+    font-family: "Inter", sans-serif;
+    font-size: 10px;
+    color: #8b949e;
+}}
+
+.zenith-todo-heatmap {{
+    margin: 4px 0 8px 0;
+    color: #00ff99;
+}}
+
+/* ── Parse Preview Chip ────────────────────────────────────────── */
+.zenith-todo-preview {{
+    margin: 6px 12px 0 12px;
+    padding: 2px 8px;
+    border-radius: 8px;
+    font-family: "JetBrainsMono Nerd Font", monospace;
+    font-size: 11px;
+    font-weight: 700;
+    color: #00ccff;
+    background: rgba(0, 204, 255, 0.10);
+}}
+
+/* ── Archive View ──────────────────────────────────────────────── */
+.zenith-todo-archive-row .zenith-todo-text {{
+    color: #8b949e;
+}}
+
+.zenith-todo-archive-date {{
+    font-family: "JetBrainsMono Nerd Font", monospace;
+    font-size: 10px;
+    color: #6e7681;
+}}
+
+.zenith-todo-restore-btn {{
+    background: transparent;
+    border: none;
+    box-shadow: none;
+    min-height: 0;
+    padding: 2px 8px;
+    border-radius: 6px;
+    font-size: 11px;
+    font-weight: 600;
+    color: #00ff99;
+}}
+
+.zenith-todo-restore-btn:hover {{
+    background: rgba(0, 255, 153, 0.12);
+}}
+
+/* ── Undo Toast ────────────────────────────────────────────────── */
+.zenith-todo-toast {{
+    margin: 4px 10px 0 10px;
+    padding: 4px 6px 4px 12px;
+    border-radius: 8px;
+    background: #161b22;
+    border: 1px solid #30363d;
+}}
+
+.zenith-todo-toast-text {{
+    font-family: "Inter", sans-serif;
+    font-size: 12px;
+    color: #cdd6f4;
+}}
+
+.zenith-todo-toast-btn {{
+    background: transparent;
+    border: none;
+    box-shadow: none;
+    min-height: 0;
+    padding: 2px 8px;
+    border-radius: 6px;
+    font-family: "Inter", sans-serif;
+    font-size: 12px;
+    font-weight: 700;
+    color: #00ccff;
+}}
+
+.zenith-todo-toast-btn:hover {{
+    background: rgba(0, 204, 255, 0.12);
+}}
+
 /* ── Input Row ─────────────────────────────────────────────────── */
 .zenith-todo-input-row {{
     padding: 8px 10px 10px 10px;
@@ -0,0 +1,66 @@
+//! Noticing when wall-clock time jumps. GLib timers run on the monotonic
+//! clock, which stands still during suspend, so anything that shows the time
+//! re-syncs on resume and when the time zone or clock setting changes.
+
+use gtk4::gio;
+use std::rc::Rc;
+
+const LOGIN1_NAME: &str = "org.freedesktop.login1";
+const LOGIN1_PATH: &str = "/org/freedesktop/login1";
+const LOGIN1_MANAGER: &str = "org.freedesktop.login1.Manager";
+const TIMEDATE1_NAME: &str = "org.freedesktop.timedate1";
+const TIMEDATE1_PATH: &str = "/org/freedesktop/timedate1";
+
+/// Subscriptions on the system bus; dropping this stops the callbacks.
+pub struct TimeJumps {
+    _subscriptions: [gio::SignalSubscription; 2],
+}
+
+impl TimeJumps {
+    /// Call `on_jump` after a resume from suspend (logind's
+    /// `PrepareForSleep(false)`) and whenever timedated reports a changed
+    /// time zone or clock setting. Returns `None` (and logs) without a
+    /// system bus.
+    pub fn watch(on_jump: impl Fn() + 'static) -> Option<Self> {
+        let conn = match gio::bus_get_sync(gio::BusType::System, gio::Cancellable::NONE) {
+            Ok(c) => c,
+            Err(e) => {
+                log::warn!("No system bus, the clock will not re-sync after suspend: {e}");
+                return None;
+            }
+        };
+        let on_jump = Rc::new(on_jump);
+
+        let resumed = conn.subscribe_to_signal(
+            Some(LOGIN1_NAME),
+            Some(LOGIN1_MANAGER),
+            Some("PrepareForSleep"),
+            Some(LOGIN1_PATH),
+            None,
+            gio::DBusSignalFlags::NONE,
+            {
+                let on_jump = Rc::clone(&on_jump);
+                move |signal| {
+                    // `true` is sent going to sleep, `false` on waking up.
+                    if signal.parameters.get::<(bool,)>() == Some((false,)) {
+                        on_jump();
+                    }
+                }
+            },
+        );
+
+        let changed = conn.subscribe_to_signal(
+            Some(TIMEDATE1_NAME),
+            Some("org.freedesktop.DBus.Properties"),
+            Some("PropertiesChanged"),
+            Some(TIMEDATE1_PATH),
+            None,
+            gio::DBusSignalFlags::NONE,
+            move |_| on_jump(),
+        );
+
+        Some(Self {
+            _subscriptions: [resumed, changed],
+        })
+    }
+}
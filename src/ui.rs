@@ -68,26 +68,36 @@ pub fn build_bar(app: &Application, cfg: &ZenithConfig) -> Result<()> {
         time_container.set_halign(gtk4::Align::Center);
 
         // Date (clickable → calendar popover)
-        let calendar = modules::calendar::create();
+        let calendar = modules::calendar::create(&cfg.modules);
         time_container.append(&calendar);
 
         // Arch logo separator
-        let logo = gtk4::Label::new(Some("\u{f303}")); // Nerd Font: 
+        let logo = gtk4::Label::new(Some("\u{f303}")); // Nerd Font: 
         logo.add_css_class("zenith-logo");
         time_container.append(&logo);
 
         // Clock (ticking time)
-        let clock = modules::clock::create(&cfg.modules.clock_format);
+        let clock = modules::clock::create(&cfg.modules);
         time_container.append(&clock);
 
         center_box.set_center_widget(Some(&time_container));
     }
 
-    // Left: Todo module
-    if cfg.modules.todo {
-        let todo = modules::todo::create();
-        center_box.set_start_widget(Some(&todo));
+    // Left: Todo module, then the pomodoro timer
+    let left = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+    left.set_halign(gtk4::Align::Start);
+    let tasks = if cfg.modules.todo {
+        let (todo, tasks) = modules::todo::create(&cfg.todo);
+        left.append(&todo);
+        Some(tasks)
+    } else {
+        None
+    };
+    if cfg.modules.pomodoro {
+        let pomodoro = modules::pomodoro::create(&cfg.pomodoro, tasks);
+        left.append(&pomodoro);
     }
+    center_box.set_start_widget(Some(&left));
 
     // Right: System stats / tray placeholder
     if cfg.modules.system_stats {